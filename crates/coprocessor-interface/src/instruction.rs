@@ -0,0 +1,115 @@
+// crates/coprocessor-interface/src/instruction.rs
+//! 명령 인자 구조체 - 데이터 = sha256("global:<ix_name>")[..8] || borsh(인자)
+//! 필드 순서는 프로그램 핸들러의 인자 순서와 같음 (프로그램 테스트에서 대조)
+use anchor_lang::prelude::*;
+use anchor_lang::{Discriminator, InstructionData};
+
+use crate::{CallbackSpec, OracleGuard};
+
+macro_rules! instruction_data {
+    ($name:ident, $disc:expr) => {
+        impl Discriminator for $name {
+            const DISCRIMINATOR: &'static [u8] = &$disc;
+        }
+        impl InstructionData for $name {}
+    };
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SubmitJobInlineArgs {
+    pub commitment: [u8; 32],
+    pub da_ptr_hash: Option<[u8; 32]>,
+    pub reveal_after_slot: u64,
+    pub function_id: u16,
+    pub context_data: [u8; 32],
+    pub ir_bytes: Vec<u8>,
+    pub oracle_guard: Option<OracleGuard>,
+    pub callback: Option<CallbackSpec>,
+    pub parent_jobs: Vec<Pubkey>,
+}
+instruction_data!(SubmitJobInlineArgs, [29, 68, 225, 188, 99, 165, 99, 255]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SubmitJobByBufferArgs {
+    pub commitment: [u8; 32],
+    pub da_ptr_hash: Option<[u8; 32]>,
+    pub reveal_after_slot: u64,
+    pub function_id: u16,
+    pub context_data: [u8; 32],
+    pub oracle_guard: Option<OracleGuard>,
+    pub callback: Option<CallbackSpec>,
+    pub parent_jobs: Vec<Pubkey>,
+}
+instruction_data!(SubmitJobByBufferArgs, [37, 139, 153, 82, 224, 145, 255, 103]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SubmitJobByDigestArgs {
+    pub commitment: [u8; 32],
+    pub da_ptr_hash: Option<[u8; 32]>,
+    pub reveal_after_slot: u64,
+    pub function_id: u16,
+    pub context_data: [u8; 32],
+    pub oracle_guard: Option<OracleGuard>,
+    pub callback: Option<CallbackSpec>,
+    pub parent_jobs: Vec<Pubkey>,
+}
+instruction_data!(SubmitJobByDigestArgs, [211, 29, 210, 194, 81, 51, 218, 33]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct FinalizeArgs {}
+instruction_data!(FinalizeArgs, [171, 61, 218, 56, 127, 115, 12, 217]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RevealResultArgs {
+    pub result: u64,
+    pub salt: [u8; 32],
+    pub final_step: u32,
+    pub final_registers: [u64; 16],
+}
+instruction_data!(RevealResultArgs, [251, 165, 27, 86, 52, 234, 133, 173]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MarkConsumedArgs {}
+instruction_data!(MarkConsumedArgs, [251, 118, 234, 3, 120, 114, 252, 61]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CloseJobArgs {}
+instruction_data!(CloseJobArgs, [90, 100, 180, 200, 200, 163, 120, 182]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CancelJobArgs {}
+instruction_data!(CancelJobArgs, [126, 241, 155, 241, 50, 236, 83, 118]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InitIrBufferArgs {
+    pub buffer_id: u64,
+    pub total_len: u32,
+}
+instruction_data!(InitIrBufferArgs, [241, 54, 140, 182, 248, 69, 125, 137]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WriteIrChunkArgs {
+    pub offset: u32,
+    pub chunk: Vec<u8>,
+}
+instruction_data!(WriteIrChunkArgs, [31, 250, 76, 50, 141, 192, 192, 94]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SealIrBufferArgs {}
+instruction_data!(SealIrBufferArgs, [188, 242, 208, 68, 235, 122, 143, 111]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CloseIrBufferArgs {}
+instruction_data!(CloseIrBufferArgs, [121, 30, 96, 35, 232, 26, 115, 139]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ChallengeResultArgs {
+    pub evidence_hash: [u8; 32],
+}
+instruction_data!(ChallengeResultArgs, [62, 59, 36, 3, 171, 25, 241, 163]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ChallengeMetricsArgs {
+    pub evidence_hash: [u8; 32],
+}
+instruction_data!(ChallengeMetricsArgs, [160, 21, 48, 201, 19, 73, 160, 4]);
@@ -0,0 +1,33 @@
+// crates/coprocessor-interface/src/lib.rs
+//! confidential_coprocessor 공용 인터페이스 - 프로그램, 소비 프로그램, 오프체인 도구가 같은 정의를 공유
+//! - state: 프로그램이 소유하는 모든 계정 레이아웃 (Config, Job, 오라클/지표/분쟁 계정 등)
+//! - instruction: 명령 인자 구조체와 디스크리미네이터
+//! - pda: PDA 시드와 주소 헬퍼
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+pub use state::*;
+
+declare_id!("CCxx3Q6jHtuXDndGJ5xHndGmA9v5YZoAQN7rSK6GQX9S");
+
+/// OracleSnapshot.ptr_hash = keccak("oracle-snap-v1" || program || feed || price || conf || slot)
+pub fn oracle_ptr_hash(
+    oracle_program: &Pubkey,
+    feed: &Pubkey,
+    price_e9: i64,
+    conf_e9: u64,
+    observed_slot: u64,
+) -> [u8; 32] {
+    hashv(&[
+        b"oracle-snap-v1",
+        oracle_program.as_ref(),
+        feed.as_ref(),
+        &price_e9.to_le_bytes(),
+        &conf_e9.to_le_bytes(),
+        &observed_slot.to_le_bytes(),
+    ]).to_bytes()
+}
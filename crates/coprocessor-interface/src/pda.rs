@@ -0,0 +1,100 @@
+// crates/coprocessor-interface/src/pda.rs
+//! PDA 시드와 주소 헬퍼 - 프로그램의 seeds 제약도 같은 상수를 사용
+use anchor_lang::prelude::*;
+
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const JOB_SEED: &[u8] = b"job";
+pub const FUNCTION_SEED: &[u8] = b"function";
+pub const EXECUTOR_SEED: &[u8] = b"executor";
+pub const CODE_SEED: &[u8] = b"code";
+pub const ORACLE_SNAPSHOT_SEED: &[u8] = b"oraclesnap";
+pub const CALLER_SEED: &[u8] = b"coproc-caller";
+pub const IR_BUFFER_SEED: &[u8] = b"ir-buffer";
+pub const DISPUTE_SEED: &[u8] = b"dispute";
+pub const METRICS_SEED: &[u8] = b"metrics";
+pub const METRICS_COMMIT_SEED: &[u8] = b"metrics-commit";
+pub const ORACLE_BUNDLE_SEED: &[u8] = b"oraclebundle";
+pub const ORACLE_AGG_SEED: &[u8] = b"oracleagg";
+
+/// ["config"]
+pub fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], &crate::ID)
+}
+
+/// ["job", commitment, submitter]
+pub fn job_pda(commitment: &[u8; 32], submitter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[JOB_SEED, commitment, submitter.as_ref()], &crate::ID)
+}
+
+/// ["function", consumer_program, function_id(le)]
+pub fn function_pda(consumer_program: &Pubkey, function_id: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FUNCTION_SEED, consumer_program.as_ref(), &function_id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// ["executor", executor]
+pub fn executor_pda(executor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EXECUTOR_SEED, executor.as_ref()], &crate::ID)
+}
+
+/// ["code", digest]
+pub fn code_pda(digest: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CODE_SEED, digest], &crate::ID)
+}
+
+/// ["oraclesnap", oracle_program, feed, observed_slot(le)]
+pub fn oracle_snapshot_pda(oracle_program: &Pubkey, feed: &Pubkey, observed_slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ORACLE_SNAPSHOT_SEED, oracle_program.as_ref(), feed.as_ref(), &observed_slot.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// ["ir-buffer", owner, buffer_id(le)]
+pub fn ir_buffer_pda(owner: &Pubkey, buffer_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[IR_BUFFER_SEED, owner.as_ref(), &buffer_id.to_le_bytes()], &crate::ID)
+}
+
+/// ["dispute", job]
+pub fn dispute_pda(job: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DISPUTE_SEED, job.as_ref()], &crate::ID)
+}
+
+/// ["metrics"]
+pub fn metrics_history_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METRICS_SEED], &crate::ID)
+}
+
+/// ["metrics-commit", window_start_slot(le)]
+pub fn metrics_commitment_pda(window_start_slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METRICS_COMMIT_SEED, &window_start_slot.to_le_bytes()], &crate::ID)
+}
+
+/// ["oraclebundle", recorder, bundle_id(le)]
+pub fn oracle_bundle_pda(recorder: &Pubkey, bundle_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ORACLE_BUNDLE_SEED, recorder.as_ref(), &bundle_id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// ["oracleagg", oracle_program, feed, from_slot(le), to_slot(le)]
+pub fn oracle_agg_pda(oracle_program: &Pubkey, feed: &Pubkey, from_slot: u64, to_slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ORACLE_AGG_SEED,
+            oracle_program.as_ref(),
+            feed.as_ref(),
+            &from_slot.to_le_bytes(),
+            &to_slot.to_le_bytes(),
+        ],
+        &crate::ID,
+    )
+}
+
+/// 소비 프로그램 쪽 서명 PDA ["coproc-caller"] - 주의: program_id는 코프로세서가 아니라 소비 프로그램
+pub fn caller_authority_pda(consumer_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CALLER_SEED], consumer_program)
+}
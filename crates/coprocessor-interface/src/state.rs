@@ -0,0 +1,478 @@
+// crates/coprocessor-interface/src/state.rs
+//! 프로그램이 소유하는 계정 레이아웃 - 프로그램은 이 정의를 그대로 재노출 (오류 코드가 필요한 검증은 프로그램 쪽)
+//! - 필드는 항상 끝에 추가 (기존 접두 레이아웃 유지)
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+pub const MAX_COMMITTEE: usize = 8;
+pub const MAX_PAUSED_FUNCTIONS: usize = 16;
+pub const MAX_REQUIRED_INPUTS: usize = 4;
+
+#[account]
+pub struct Config {
+    pub authority: Pubkey,
+    pub challenge_window_slots: u64,
+    pub min_bond_lamports: u64,      // 게시에 필요한 최소 본드 = 1회 슬래싱 금액
+    pub challenger_reward_bp: u16,   // 슬래싱 금액 중 챌린저 몫 (나머지는 treasury)
+    pub treasury: Pubkey,
+    pub service_deadline_slots: u64, // 제출 후 이 기간 내 미게시 시 submitter가 취소 가능
+    pub snapshot_retention_slots: u64, // observed_slot 이후 이 기간이 지나면 스냅샷 close 가능
+    pub committee: Vec<Pubkey>,      // 최대 MAX_COMMITTEE
+    pub attestation_threshold: u8,   // 0 = 위원회 서명 불필요
+    pub pending_authority: Option<Pubkey>,
+    pub paused: bool,                // 전역 서킷 브레이커
+    pub paused_functions: Vec<u16>,  // 최대 MAX_PAUSED_FUNCTIONS
+    pub bump: u8,
+    pub challenger_stake_lamports: u64, // challenge_result 시 챌린저가 Job에 예치, 기각되면 treasury로 몰수
+    pub max_bundle_slot_spread: u64, // 오라클 번들 내 피드 관측 슬롯 차이 상한
+}
+impl Config {
+    // authority(32) + challenge_window_slots(8) + min_bond_lamports(8)
+    // + challenger_reward_bp(2) + treasury(32) + service_deadline_slots(8) + snapshot_retention_slots(8)
+    // + committee(4 + 32*MAX_COMMITTEE) + attestation_threshold(1) + pending_authority(1+32)
+    // + paused(1) + paused_functions(4 + 2*MAX_PAUSED_FUNCTIONS) + bump(1)
+    // + challenger_stake_lamports(8) + max_bundle_slot_spread(8)
+    // = 32 + 8 + 8 + 2 + 32 + 8 + 8 + 260 + 1 + 33 + 1 + 36 + 1 + 8 + 8 = 446 bytes
+    pub const SIZE: usize = 446;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum JobStatus { Submitted=0, Posted=1, Finalized=2, Revealed=3, Challenged=4 }
+
+/// post_result 시 요구되는 외부 입력 종류 (리프 계산은 프로그램의 inputs.rs)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum InputKind { OracleSnapshot=0, JobResult=1, AccountState=2, OracleBundle=3 }
+
+/// Job에 바인딩된 외부 입력 - pinned면 post_result에서 해당 계정만 허용
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JobInput {
+    pub kind: InputKind,
+    pub pinned: Option<Pubkey>,
+}
+impl JobInput {
+    // kind(1) + pinned(1+32) = 34 bytes
+    pub const SIZE: usize = 34;
+}
+
+/// 오라클 앵커 Job의 스냅샷 조건 - post_result에서 OracleSnapshot 입력마다 검사
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OracleGuard {
+    pub staleness_limit_slots: u64, // submitted_slot - observed_slot 허용 한도
+    pub conf_band_bp: u16,          // conf_e9 / |price_e9| 허용 한도
+}
+impl OracleGuard {
+    // staleness_limit_slots(8) + conf_band_bp(2) = 10 bytes
+    pub const SIZE: usize = 10;
+}
+
+/// 콜백 호출 시점
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CallbackTrigger { Finalize=0, Reveal=1 }
+
+/// 소비자 콜백 - trigger 상태 도달 후 invoke_callback으로 program_id에 CPI (계정 = [job, ...], 데이터 = discriminator)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallbackSpec {
+    pub program_id: Pubkey,     // FunctionRecord.consumer_program과 같아야 함
+    pub discriminator: [u8; 8],
+    pub trigger: CallbackTrigger,
+}
+impl CallbackSpec {
+    // program_id(32) + discriminator(8) + trigger(1) = 41 bytes
+    pub const SIZE: usize = 41;
+}
+
+#[account]
+pub struct Job {
+    pub commitment: [u8; 32],
+    pub da_ptr_hash: Option<[u8; 32]>,
+    pub expected_code_digest: [u8; 32],
+    pub result_commitment: Option<[u8; 32]>,
+    pub external_ptr_hash: Option<[u8; 32]>,
+    pub status: JobStatus,
+    pub posted_slot: Option<u64>,
+    pub reveal_after_slot: u64,
+    pub function_id: u16,
+    pub context_data: [u8; 32],
+    pub submitter: Pubkey,
+    pub bump: u8,
+    pub challenger: Option<Pubkey>,
+    pub challenge_evidence_hash: Option<[u8; 32]>,
+    pub revealed_result: Option<u64>,
+    pub executor: Option<Pubkey>,     // post_result를 게시한 실행자
+    pub submitted_slot: u64,
+    pub consumed_slot: Option<u64>,   // mark_consumed 시점 (close_job 전제조건)
+    pub consumer_program: Pubkey,     // 제출 시 사용한 FunctionRecord의 소비 프로그램
+    pub challenge_window_slots: u64,  // 제출 시점 유효 윈도우 (FunctionRecord 오버라이드 반영)
+    pub inputs: Vec<JobInput>,        // 제출 시 바인딩된 외부 입력, 최대 MAX_REQUIRED_INPUTS
+    pub oracle_guard: Option<OracleGuard>,
+    pub callback: Option<CallbackSpec>,
+    pub caller_program: Option<Pubkey>, // ["coproc-caller"] PDA로 서명해 CPI 제출한 소비 프로그램, 직접 제출이면 None
+    pub challenger_stake_lamports: u64, // 챌린지 중 Job 계정에 예치된 챌린저 스테이크
+    pub trace_commitment: Option<TraceCommitment>, // post_result 시 커밋한 IR 트레이스 시작/끝 상태
+    pub dependent_jobs: u32, // 이 Job을 JobResult 입력으로 고정한 미종료 Job 수 (0이어야 close/cancel 가능)
+    pub callback_invoked_slot: Option<u64>, // invoke_callback 성공 시점 (Job당 1회)
+}
+impl Job {
+    // commitment(32) + da_ptr_hash(1+32) + expected_code_digest(32)
+    // + result_commitment(1+32) + external_ptr_hash(1+32) + status(1) + posted_slot(1+8)
+    // + reveal_after_slot(8) + function_id(2) + context_data(32) + submitter(32) + bump(1)
+    // + challenger(1+32) + challenge_evidence_hash(1+32) + revealed_result(1+8) + executor(1+32)
+    // + submitted_slot(8) + consumed_slot(1+8) + consumer_program(32) + challenge_window_slots(8)
+    // + inputs(4 + JobInput::SIZE*MAX_REQUIRED_INPUTS) + oracle_guard(1 + OracleGuard::SIZE)
+    // + callback(1 + CallbackSpec::SIZE) + caller_program(1+32) + challenger_stake_lamports(8)
+    // + trace_commitment(1 + TraceCommitment::SIZE) + dependent_jobs(4) + callback_invoked_slot(1+8)
+    // = 32 +33 +32 +33 +33 +1 +9 +8 +2 +32 +32 +1 +33 +33 +9 +33 +8 +9 +32 +8 +140 +11 +42 +33 +8 +65 +4 +9 = 725
+    pub const SIZE: usize = 725;
+
+    /// 결과가 확정되어 소비 가능한 상태인지 (Finalized/Revealed)
+    pub fn is_settled(&self) -> bool {
+        matches!(self.status, JobStatus::Finalized | JobStatus::Revealed)
+    }
+}
+
+/// 실행자가 post_result 시 커밋하는 IR 트레이스 양 끝 상태 (분쟁 게임의 첫/마지막 리프)
+/// initial_state_hash = Job 입력에서 유도한 step 0 레지스터 상태 (on-chain 계산)
+/// final_state_hash = 실행자가 커밋한 마지막 상태 - reveal 결과는 이 상태의 출력 레지스터
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceCommitment {
+    pub initial_state_hash: [u8; 32],
+    pub final_state_hash: [u8; 32],
+}
+impl TraceCommitment {
+    // initial_state_hash(32) + final_state_hash(32) = 64 bytes
+    pub const SIZE: usize = 64;
+}
+
+#[account]
+#[derive(Default)]
+pub struct OracleSnapshot {
+    pub oracle_program: Pubkey,
+    pub feed: Pubkey,
+    pub price_e9: i64,        // 1e-9 단위
+    pub conf_e9: u64,         // 1e-9 단위의 신뢰구간(선택)
+    pub observed_slot: u64,
+    pub ptr_hash: [u8; 32],   // crate::oracle_ptr_hash로 계산
+    pub bump: u8,
+    pub recorder: Pubkey,     // close_snapshot 시 rent 환불 대상
+}
+impl OracleSnapshot {
+    // 32+32+8+8+8+32+1+32 = 153 bytes
+    pub const SIZE: usize = 153;
+}
+
+#[account]
+pub struct ExecutorRecord {
+    pub executor: Pubkey,
+    pub status: ExecutorStatus,
+    pub added_slot: u64,
+    pub bond_lamports: u64,          // rent 외에 이 PDA가 보관 중인 본드
+    pub open_challenges: u32,
+    pub last_posted_slot: u64,
+    pub bump: u8,
+    pub bond_locked_until_slot: u64, // 게시한 Job들의 챌린지 윈도우 종료 중 최댓값
+}
+impl ExecutorRecord {
+    // executor(32) + status(1) + added_slot(8) + bond_lamports(8)
+    // + open_challenges(4) + last_posted_slot(8) + bump(1) + bond_locked_until_slot(8) = 70 bytes
+    pub const SIZE: usize = 70;
+}
+
+pub const MAX_ALLOWED_DIGESTS: usize = 4;
+
+/// (consumer_program, function_id)별 함수 스펙 (PDA = ["function", consumer_program, function_id(le)])
+#[account]
+pub struct FunctionRecord {
+    pub consumer_program: Pubkey,
+    pub function_id: u16,
+    pub owner: Pubkey,                       // 스펙 변경 권한
+    pub allowed_digests: Vec<[u8; 32]>,      // 비어 있으면 코드 제한 없음, 최대 MAX_ALLOWED_DIGESTS
+    pub required_inputs: Vec<InputKind>,     // 최대 MAX_REQUIRED_INPUTS
+    pub challenge_window_slots: Option<u64>, // None이면 Config 기본값
+    pub bump: u8,
+}
+impl FunctionRecord {
+    // consumer_program(32) + function_id(2) + owner(32) + allowed_digests(4 + 32*MAX_ALLOWED_DIGESTS)
+    // + required_inputs(4 + 1*MAX_REQUIRED_INPUTS) + challenge_window_slots(1+8) + bump(1)
+    // = 32 + 2 + 32 + 132 + 8 + 9 + 1 = 216 bytes
+    pub const SIZE: usize = 216;
+
+    /// 허용 목록이 비어 있거나 digest를 포함하는지
+    pub fn is_allowed(&self, digest: &[u8; 32]) -> bool {
+        self.allowed_digests.is_empty() || self.allowed_digests.contains(digest)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ExecutorStatus { Active=0, Suspended=1 }
+
+/// 지표 히스토리 보관 윈도우 수 (30초 윈도우 기준 약 16분)
+pub const METRICS_HISTORY_LEN: usize = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MetricsWindow {
+    pub window_start_slot: u64,
+    pub published_slot: u64,
+    pub executor: Pubkey,     // 게시한 실행자 (commit_metrics 권한)
+    pub tvl: u64,
+    pub utilization_bp: u16,
+    pub total_users: u32,
+    pub proof_ptr_hash: Option<[u8; 32]>, // 기여 머클 루트 (commit_metrics로 고정)
+}
+impl MetricsWindow {
+    // window_start_slot(8) + published_slot(8) + executor(32) + tvl(8) + utilization_bp(2)
+    // + total_users(4) + proof_ptr_hash(1+32) = 95 bytes
+    pub const SIZE: usize = 95;
+}
+
+/// 최근 METRICS_HISTORY_LEN개 지표 윈도우 링 버퍼 (PDA = ["metrics"])
+/// 가득 차기 전에는 windows에 추가, 이후엔 head 위치(가장 오래된 윈도우)를 덮어씀
+#[account]
+pub struct MetricsHistory {
+    pub head: u16,                    // 다음에 기록할 인덱스
+    pub windows: Vec<MetricsWindow>,
+    pub bump: u8,
+}
+impl MetricsHistory {
+    // head(2) + windows(4 + MetricsWindow::SIZE*METRICS_HISTORY_LEN) + bump(1)
+    // = 2 +3044 +1 = 3047
+    pub const SIZE: usize = 3047;
+
+    /// window_start_slot가 최신보다 커야 기록 (아니면 false, 변경 없음)
+    pub fn push(&mut self, window: MetricsWindow) -> bool {
+        if self.latest().is_some_and(|latest| window.window_start_slot <= latest.window_start_slot) {
+            return false;
+        }
+        let head = self.head as usize;
+        if self.windows.len() < METRICS_HISTORY_LEN {
+            self.windows.push(window);
+        } else {
+            self.windows[head] = window;
+        }
+        self.head = ((head + 1) % METRICS_HISTORY_LEN) as u16;
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// 최신에서 back번째 이전 윈도우 (0 = 최신)
+    pub fn get(&self, back: usize) -> Option<&MetricsWindow> {
+        let len = self.windows.len();
+        if back >= len {
+            return None;
+        }
+        let idx = (self.head as usize + METRICS_HISTORY_LEN - 1 - back) % METRICS_HISTORY_LEN;
+        self.windows.get(idx)
+    }
+
+    pub fn latest(&self) -> Option<&MetricsWindow> {
+        self.get(0)
+    }
+
+    /// 최신 → 과거 순서
+    pub fn iter_recent(&self) -> impl Iterator<Item = &MetricsWindow> {
+        (0..self.windows.len()).filter_map(move |back| self.get(back))
+    }
+
+    /// slot이 속한 윈도우 (window_start_slot <= slot 인 가장 최근 윈도우)
+    pub fn window_at(&self, slot: u64) -> Option<&MetricsWindow> {
+        self.iter_recent().find(|w| w.window_start_slot <= slot)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MetricsStatus { Committed=0, Challenged=1, Invalidated=2 }
+
+/// 윈도우별 기여 커밋 (PDA = ["metrics-commit", window_start_slot(le)])
+#[account]
+pub struct MetricsCommitment {
+    pub executor: Pubkey,
+    pub window_start_slot: u64,
+    pub tvl: u64,
+    pub contributions_root: [u8; 32], // merkle_root(contribution_leaf(job, balance_handle))
+    pub contribution_count: u32,
+    pub committed_slot: u64,
+    pub status: MetricsStatus,
+    pub challenger: Option<Pubkey>,
+    pub challenge_evidence_hash: Option<[u8; 32]>,
+    pub bump: u8,
+    pub challenger_stake_lamports: u64, // 챌린지 중 커밋먼트 계정에 예치된 챌린저 스테이크
+}
+impl MetricsCommitment {
+    // executor(32) + window_start_slot(8) + tvl(8) + contributions_root(32) + contribution_count(4)
+    // + committed_slot(8) + status(1) + challenger(1+32) + challenge_evidence_hash(1+32) + bump(1)
+    // + challenger_stake_lamports(8)
+    // = 32 +8 +8 +32 +4 +8 +1 +33 +33 +1 +8 = 168
+    pub const SIZE: usize = 168;
+}
+
+/// 집계 한 번에 넣을 수 있는 최대 스냅샷 수
+pub const MAX_AGGREGATE_SAMPLES: usize = 16;
+
+/// 스냅샷 구간 집계 (PDA = ["oracleagg", oracle_program, feed, from_slot(le), to_slot(le)])
+/// Job 입력에서는 OracleSnapshot 자리에 그대로 쓸 수 있음 (가격 = TWAP, 관측 슬롯 = 마지막 샘플 슬롯)
+#[account]
+pub struct AggregatedSnapshot {
+    pub oracle_program: Pubkey,
+    pub feed: Pubkey,
+    pub from_slot: u64,
+    pub to_slot: u64,
+    pub twap_e9: i64,
+    pub median_e9: i64,
+    pub min_e9: i64,
+    pub max_e9: i64,
+    pub conf_e9: u64,         // 샘플 중 가장 넓은 신뢰구간
+    pub sample_count: u16,
+    pub ptr_hash: [u8; 32],   // oracle-agg-v1 해시
+    pub bump: u8,
+    pub recorder: Pubkey,
+    pub observed_slot: u64,   // 마지막 샘플 슬롯 (oracle_guard 신선도 기준)
+}
+impl AggregatedSnapshot {
+    // 32+32+8+8+8+8+8+8+8+2+32+1+32+8 = 195 bytes
+    pub const SIZE: usize = 195;
+
+    pub fn compute_ptr_hash(&self) -> [u8; 32] {
+        hashv(&[
+            b"oracle-agg-v1",
+            self.oracle_program.as_ref(),
+            self.feed.as_ref(),
+            &self.from_slot.to_le_bytes(),
+            &self.to_slot.to_le_bytes(),
+            &self.twap_e9.to_le_bytes(),
+            &self.median_e9.to_le_bytes(),
+            &self.min_e9.to_le_bytes(),
+            &self.max_e9.to_le_bytes(),
+            &self.conf_e9.to_le_bytes(),
+            &self.sample_count.to_le_bytes(),
+        ]).to_bytes()
+    }
+}
+
+/// 번들 하나에 담을 수 있는 최대 피드 수
+pub const MAX_BUNDLE_FEEDS: usize = 8;
+
+/// 번들 안의 피드 한 개 (OracleSnapshot과 같은 값, 같은 oracle_ptr_hash)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BundleEntry {
+    pub oracle_program: Pubkey,
+    pub feed: Pubkey,
+    pub price_e9: i64,
+    pub conf_e9: u64,
+    pub observed_slot: u64,
+}
+impl BundleEntry {
+    // oracle_program(32) + feed(32) + price_e9(8) + conf_e9(8) + observed_slot(8) = 88 bytes
+    pub const SIZE: usize = 88;
+
+    pub fn ptr_hash(&self) -> [u8; 32] {
+        crate::oracle_ptr_hash(&self.oracle_program, &self.feed, self.price_e9, self.conf_e9, self.observed_slot)
+    }
+}
+
+/// 다중 피드 스냅샷 (PDA = ["oraclebundle", recorder, bundle_id(le)])
+#[account]
+pub struct OracleBundle {
+    pub recorder: Pubkey,
+    pub bundle_id: u64,
+    pub entries: Vec<BundleEntry>,
+    pub min_slot: u64,        // 가장 이른 관측 슬롯
+    pub max_slot: u64,        // 가장 늦은 관측 슬롯
+    pub ptr_hash: [u8; 32],   // merkle_root(entries[i].ptr_hash())
+    pub bump: u8,
+}
+impl OracleBundle {
+    // recorder(32) + bundle_id(8) + entries(4 + BundleEntry::SIZE*MAX_BUNDLE_FEEDS)
+    // + min_slot(8) + max_slot(8) + ptr_hash(32) + bump(1)
+    // = 32 +8 +708 +8 +8 +32 +1 = 797
+    pub const SIZE: usize = 797;
+}
+
+/// 버퍼 모드 IR - 계정 생성 한도(10KB) 안에서 MAX_BUFFERED_IR_BYTES까지
+pub const MAX_BUFFERED_IR_BYTES: usize = 8192;
+
+#[account]
+pub struct IrBuffer {
+    pub owner: Pubkey,
+    pub buffer_id: u64,
+    pub total_len: u32,
+    pub written_len: u32,     // 순차 기록된 바이트 수
+    pub sealed: bool,
+    pub digest: [u8; 32],     // seal 시 sha256(data) - inline 모드와 동일
+    pub bump: u8,
+    pub data: Vec<u8>,        // total_len 바이트
+}
+impl IrBuffer {
+    // owner(32) + buffer_id(8) + total_len(4) + written_len(4) + sealed(1) + digest(32) + bump(1)
+    // + data len prefix(4) = 86 bytes (+ total_len 데이터)
+    pub const HEADER_SIZE: usize = 86;
+
+    pub fn space(total_len: u32) -> usize {
+        8 + Self::HEADER_SIZE + total_len as usize
+    }
+}
+
+pub const MAX_CODE_NAME_LEN: usize = 32;
+
+/// 등록된 IR 프로그램 (PDA = ["code", digest])
+#[account]
+pub struct CodeRecord {
+    pub digest: [u8; 32],             // sha256(IR) - Job.expected_code_digest와 동일 형식
+    pub registrar: Pubkey,
+    pub name: String,                 // 최대 MAX_CODE_NAME_LEN
+    pub version: u16,
+    pub op_count: u32,
+    pub da_ptr_hash: Option<[u8; 32]>, // 바이트를 온체인에 두지 않는 경우의 DA 포인터
+    pub registered_slot: u64,
+    pub bump: u8,
+    pub ir: Vec<u8>,                  // 온체인 보관 IR (DA 전용이면 비어 있음)
+}
+impl CodeRecord {
+    // digest(32) + registrar(32) + name(4+MAX_CODE_NAME_LEN) + version(2) + op_count(4)
+    // + da_ptr_hash(1+32) + registered_slot(8) + bump(1) + ir len prefix(4) = 152 bytes (+ IR 바이트)
+    pub const HEADER_SIZE: usize = 152;
+
+    pub fn space(ir_len: usize) -> usize {
+        8 + Self::HEADER_SIZE + ir_len
+    }
+}
+
+#[account]
+pub struct Dispute {
+    pub job: Pubkey,
+    pub challenger: Pubkey,
+    pub defender: Pubkey,
+    pub challenger_root: [u8; 32],
+    pub defender_root: Option<[u8; 32]>,
+    pub step_count: u32,
+    pub lo: u32,                      // 양측이 합의한 마지막 스텝
+    pub hi: u32,                      // 양측이 불일치하는 첫 스텝 후보
+    pub lo_hash: [u8; 32],
+    pub hi_challenger_hash: [u8; 32],
+    pub hi_defender_hash: [u8; 32],
+    pub pending_mid_hash: Option<[u8; 32]>, // 실행자가 공개한 mid 리프 (챌린저 응수 대기)
+    pub phase: DisputePhase,
+    pub last_move_slot: u64,
+    pub bump: u8,
+}
+impl Dispute {
+    // job(32) + challenger(32) + defender(32) + challenger_root(32) + defender_root(1+32)
+    // + step_count(4) + lo(4) + hi(4) + lo_hash(32) + hi_challenger_hash(32) + hi_defender_hash(32)
+    // + pending_mid_hash(1+32) + phase(1) + last_move_slot(8) + bump(1)
+    // = 32 +32 +32 +32 +33 +4 +4 +4 +32 +32 +32 +33 +1 +8 +1 = 312
+    pub const SIZE: usize = 312;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DisputePhase { AwaitingResponse=0, DefenderTurn=1, ChallengerTurn=2, OneStep=3 }
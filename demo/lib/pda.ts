@@ -2,7 +2,7 @@ import { PublicKey } from '@solana/web3.js';
 import { BN } from '@coral-xyz/anchor';
 
 // Program IDs (replace with your actual deployed addresses)
-export const COPROC_PROGRAM_ID = new PublicKey('9FWBcP2fTpjvYpSQmZZm2NkouBifLw5rKtNPyfzkA4zJ');
+export const COPROC_PROGRAM_ID = new PublicKey('CCxx3Q6jHtuXDndGJ5xHndGmA9v5YZoAQN7rSK6GQX9S');
 export const LENDING_PROGRAM_ID = new PublicKey('7416mML15yRamg6KTbemgwBZDsXoVmws328Tp8W7Za9y');
 
 // Job PDA calculation
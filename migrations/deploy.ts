@@ -42,8 +42,14 @@ module.exports = async function (provider: anchor.AnchorProvider) {
     // Initialize with deployer as both authority and executor
     const tx = await coproc.methods
       .initializeConfig(
-        provider.wallet.publicKey, // executor = deployer
-        new anchor.BN(0) // challenge_window_slots = 0 for demo
+        new anchor.BN(0), // challenge_window_slots = 0 for demo
+        new anchor.BN(0), // min_bond_lamports = 0 for demo
+        5000,             // challenger_reward_bp = 50%
+        provider.wallet.publicKey, // treasury = deployer
+        new anchor.BN(1500), // service_deadline_slots ≈ 10 min
+        new anchor.BN(216_000), // snapshot_retention_slots ≈ 1 day
+        new anchor.BN(0), // challenger_stake_lamports = 0 for demo
+        new anchor.BN(25) // max_bundle_slot_spread ≈ 10 s
       )
       .accounts({
         config: configPda,
@@ -52,6 +58,61 @@ module.exports = async function (provider: anchor.AnchorProvider) {
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc({ commitment: 'confirmed' });
+
+    // Register deployer as the first executor
+    const [executorRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("executor"), provider.wallet.publicKey.toBuffer()],
+      coproc.programId
+    );
+    await coproc.methods
+      .addExecutor(provider.wallet.publicKey)
+      .accounts({
+        config: configPda,
+        executorRecord: executorRecordPda,
+        authority: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    // Metrics history ring buffer (publish_metrics writes into it)
+    const [metricsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metrics")],
+      coproc.programId
+    );
+    await coproc.methods
+      .initMetricsHistory()
+      .accounts({
+        config: configPda,
+        metricsHistory: metricsPda,
+        authority: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    // Register lending_demo functions (FID 400 requires an oracle snapshot input)
+    const lending = anchor.workspace.lendingDemo;
+    for (const [functionId, requiredInputs] of [
+      [100, []],
+      [200, []],
+      [300, []],
+      [400, [{ oracleSnapshot: {} }]],
+    ] as [number, object[]][]) {
+      const fid = Buffer.alloc(2);
+      fid.writeUInt16LE(functionId);
+      const [functionRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("function"), lending.programId.toBuffer(), fid],
+        coproc.programId
+      );
+      await coproc.methods
+        .registerFunction(lending.programId, functionId, provider.wallet.publicKey, [], requiredInputs, null)
+        .accounts({
+          config: configPda,
+          functionRecord: functionRecordPda,
+          authority: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+    }
       
     console.log("✅ Config initialized!");
     console.log("   Config PDA:", configPda.toBase58());
@@ -0,0 +1,88 @@
+// programs/confidential_coprocessor/src/attestation.rs
+//! 위원회 서명 검증 - 같은 트랜잭션의 Ed25519 프리컴파일 instruction을 introspection
+//! - 런타임이 Ed25519 instruction의 서명을 이미 검증했으므로 여기서는
+//!   (pubkey, message) 쌍만 읽어 위원회 멤버/메시지 일치 여부를 센다
+//! - 오프셋은 반드시 해당 Ed25519 instruction 자신의 데이터를 가리켜야 함 (index = u16::MAX)
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+use crate::ErrorCode;
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+const MESSAGE_SIZE: usize = 32;
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// 위원회가 서명하는 메시지 = keccak("post-result-v1" || job || result_commitment || code_digest)
+pub fn post_result_message(job: &Pubkey, result_commitment: &[u8; 32], code_digest: &[u8; 32]) -> [u8; 32] {
+    hashv(&[
+        b"post-result-v1",
+        job.as_ref(),
+        result_commitment,
+        code_digest,
+    ]).to_bytes()
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16> {
+    let bytes = data.get(at..at + 2).ok_or(ErrorCode::InvalidAttestation)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// 하나의 Ed25519 instruction 데이터에서 (pubkey, message) 목록 추출
+fn signed_pairs(data: &[u8]) -> Result<Vec<(Pubkey, &[u8])>> {
+    let count = *data.first().ok_or(ErrorCode::InvalidAttestation)? as usize;
+    let mut pairs = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let base = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_SIZE;
+        let sig_ix = read_u16(data, base + 2)?;
+        let pubkey_offset = read_u16(data, base + 4)? as usize;
+        let pubkey_ix = read_u16(data, base + 6)?;
+        let msg_offset = read_u16(data, base + 8)? as usize;
+        let msg_size = read_u16(data, base + 10)? as usize;
+        let msg_ix = read_u16(data, base + 12)?;
+
+        require!(
+            sig_ix == CURRENT_INSTRUCTION && pubkey_ix == CURRENT_INSTRUCTION && msg_ix == CURRENT_INSTRUCTION,
+            ErrorCode::InvalidAttestation
+        );
+        let pubkey = data
+            .get(pubkey_offset..pubkey_offset + PUBKEY_SIZE)
+            .ok_or(ErrorCode::InvalidAttestation)?;
+        let message = data
+            .get(msg_offset..msg_offset + msg_size)
+            .ok_or(ErrorCode::InvalidAttestation)?;
+        pairs.push((Pubkey::try_from(pubkey).map_err(|_| ErrorCode::InvalidAttestation)?, message));
+    }
+    Ok(pairs)
+}
+
+/// 트랜잭션 내 Ed25519 instruction들 중 message에 서명한 서로 다른 위원회 멤버 수
+pub fn count_committee_signatures(
+    instructions_sysvar: &AccountInfo,
+    committee: &[Pubkey],
+    message: &[u8; 32],
+) -> Result<usize> {
+    let mut signed = vec![false; committee.len()];
+    let mut index = 0usize;
+
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+        for (pubkey, msg) in signed_pairs(&ix.data)? {
+            if msg.len() != MESSAGE_SIZE || msg != message {
+                continue;
+            }
+            if let Some(pos) = committee.iter().position(|m| m == &pubkey) {
+                signed[pos] = true;
+            }
+        }
+    }
+
+    Ok(signed.iter().filter(|s| **s).count())
+}
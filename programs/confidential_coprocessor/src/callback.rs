@@ -0,0 +1,57 @@
+// programs/confidential_coprocessor/src/callback.rs
+//! 소비자 콜백 - finalize/reveal_result 이후 별도 invoke_callback 명령으로 소비 프로그램에 CPI
+//! - 콜백 실패는 그 명령만 되돌리므로 Job 확정/공개를 막지 못함 (best-effort, 성공 시 1회로 제한)
+//! - remaining_accounts[0] = 콜백 프로그램, 나머지는 콜백 계정으로 그대로 전달
+//! - 콜백 계정 = [job(읽기 전용), remaining_accounts[1..]], 데이터 = discriminator
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+
+use crate::{CallbackInvoked, CallbackTrigger, ErrorCode, Job, JobStatus};
+
+/// job.callback의 trigger 상태에 도달했으면 CPI - 호출 전에 실행 기록을 계정 데이터에 먼저 기록
+pub fn invoke_callback<'info>(job: &mut Account<'info, Job>, remaining: &[AccountInfo<'info>]) -> Result<()> {
+    let spec = job.callback.ok_or(ErrorCode::NoCallback)?;
+    let reached = match spec.trigger {
+        CallbackTrigger::Finalize => job.is_settled(),
+        CallbackTrigger::Reveal => job.status == JobStatus::Revealed,
+    };
+    require!(reached, ErrorCode::InvalidJobStatus);
+    require!(job.callback_invoked_slot.is_none(), ErrorCode::CallbackAlreadyInvoked);
+
+    let (program, forwarded) = remaining.split_first().ok_or(ErrorCode::CallbackProgramMissing)?;
+    require_keys_eq!(program.key(), spec.program_id, ErrorCode::CallbackProgramMismatch);
+    require!(program.executable, ErrorCode::CallbackProgramMismatch);
+
+    // 콜백이 job을 읽으므로 실행 기록을 먼저 직렬화 (콜백이 되돌리면 기록도 함께 취소)
+    let slot = Clock::get()?.slot;
+    job.callback_invoked_slot = Some(slot);
+    job.exit(&crate::ID)?;
+
+    let mut accounts = Vec::with_capacity(1 + forwarded.len());
+    accounts.push(AccountMeta::new_readonly(job.key(), false));
+    accounts.extend(forwarded.iter().map(|info| {
+        if info.is_writable {
+            AccountMeta::new(info.key(), info.is_signer)
+        } else {
+            AccountMeta::new_readonly(info.key(), info.is_signer)
+        }
+    }));
+    let ix = Instruction { program_id: spec.program_id, accounts, data: spec.discriminator.to_vec() };
+
+    let mut infos = Vec::with_capacity(2 + forwarded.len());
+    infos.push(job.to_account_info());
+    infos.extend(forwarded.iter().cloned());
+    infos.push(program.clone());
+    invoke(&ix, &infos)?;
+
+    emit!(CallbackInvoked {
+        job: job.key(),
+        program: spec.program_id,
+        trigger: spec.trigger,
+        slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,274 @@
+// programs/confidential_coprocessor/src/cpi_client.rs
+//! 소비 프로그램용 타입 CPI 헬퍼 (cpi feature) - 호출 전에 프로그램 ID/PDA를 검증하고 crate::cpi로 위임
+//! - 인자는 coprocessor-interface의 *Args 구조체, 계정은 crate::cpi::accounts
+//! - 범위: 프로그램(PDA)이 서명자가 될 수 있는 제출자/소비자/챌린저 명령 전부
+//!   (Job 제출·확정·공개·소비·정리, IR 버퍼, 결과/메트릭 챌린지)
+//! - 제외: authority 전용(config, 함수/실행자 레지스트리, 챌린지 판정)과 등록 실행자 전용
+//!   (post_result, 분쟁 대응, 메트릭 게시, 오라클 기록) 명령 - 필요하면 crate::cpi::* 직접 사용
+//! - invoke_callback은 호출 프로그램으로 재진입할 수 있어 CPI 대상이 아님 (트랜잭션 최상위에서 호출)
+use anchor_lang::error::ErrorCode as AnchorError;
+use anchor_lang::prelude::*;
+use coprocessor_interface::instruction::{
+    ChallengeMetricsArgs, ChallengeResultArgs, InitIrBufferArgs, RevealResultArgs,
+    SubmitJobByBufferArgs, SubmitJobByDigestArgs, SubmitJobInlineArgs, WriteIrChunkArgs,
+};
+use coprocessor_interface::{pda, IrBuffer, Job, MetricsCommitment};
+
+use crate::cpi::accounts::{
+    CancelJob, ChallengeMetrics, ChallengeResult, CloseIrBuffer, CloseJob, Finalize, InitIrBuffer,
+    MarkConsumed, RevealResult, SubmitJob, SubmitJobByBuffer, SubmitJobByDigest, WriteIrBuffer,
+};
+
+fn require_program(program: &AccountInfo) -> Result<()> {
+    require_keys_eq!(*program.key, crate::ID, AnchorError::InvalidProgramId);
+    Ok(())
+}
+
+fn require_pda(account: &AccountInfo, expected: (Pubkey, u8)) -> Result<()> {
+    require_keys_eq!(*account.key, expected.0, AnchorError::ConstraintSeeds);
+    Ok(())
+}
+
+/// 코프로세서 소유 계정을 역직렬화 (PDA 재유도에 필요한 필드 읽기용)
+fn load<T: AccountDeserialize>(account: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*account.owner, crate::ID, AnchorError::ConstraintOwner);
+    T::try_deserialize(&mut &account.try_borrow_data()?[..])
+}
+
+/// Job 계정의 (commitment, submitter)로 ["job", ..] PDA 재유도 후 대조
+fn require_job_pda(job: &AccountInfo) -> Result<Job> {
+    let data: Job = load(job)?;
+    require_pda(job, pda::job_pda(&data.commitment, &data.submitter))?;
+    Ok(data)
+}
+
+/// 기존 IR 버퍼의 (owner, buffer_id)로 PDA 재유도 후 대조
+fn require_ir_buffer_pda(ir_buffer: &AccountInfo, owner: &AccountInfo) -> Result<()> {
+    let data: IrBuffer = load(ir_buffer)?;
+    require_keys_eq!(data.owner, *owner.key, AnchorError::ConstraintHasOne);
+    require_pda(ir_buffer, pda::ir_buffer_pda(owner.key, data.buffer_id))
+}
+
+/// 제출 공통 검증 - config, job(commitment, submitter), function_record(consumer_program, function_id),
+/// caller_authority(consumer_program의 ["coproc-caller"], CPI 제출이면 필수)
+fn require_submit_pdas(
+    config: &AccountInfo,
+    job: &AccountInfo,
+    function_record: &AccountInfo,
+    submitter: &AccountInfo,
+    caller_authority: Option<&AccountInfo>,
+    consumer_program: &Pubkey,
+    commitment: &[u8; 32],
+    function_id: u16,
+) -> Result<()> {
+    require_pda(config, pda::config_pda())?;
+    require_pda(job, pda::job_pda(commitment, submitter.key))?;
+    require_pda(function_record, pda::function_pda(consumer_program, function_id))?;
+    let caller_authority = caller_authority.ok_or(crate::ErrorCode::CallerAuthorityMissing)?;
+    require_pda(caller_authority, pda::caller_authority_pda(consumer_program))
+}
+
+/// 인라인 IR Job 제출 - consumer_program은 FunctionRecord를 등록한 소비 프로그램 (= 호출자 자신)
+/// caller_authority = 호출자의 ["coproc-caller"] PDA, signer_seeds로 서명 (Job.caller_program으로 기록)
+/// 함수의 required_inputs 계정은 순서대로 remaining_accounts로 전달 (제출 시 고정 필수)
+pub fn submit_job_inline<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SubmitJob<'info>>,
+    consumer_program: &Pubkey,
+    args: SubmitJobInlineArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    let a = &ctx.accounts;
+    require_submit_pdas(
+        &a.config,
+        &a.job,
+        &a.function_record,
+        &a.submitter,
+        a.caller_authority.as_ref(),
+        consumer_program,
+        &args.commitment,
+        args.function_id,
+    )?;
+    crate::cpi::submit_job_inline(
+        ctx,
+        args.commitment,
+        args.da_ptr_hash,
+        args.reveal_after_slot,
+        args.function_id,
+        args.context_data,
+        args.ir_bytes,
+        args.oracle_guard,
+        args.callback,
+        args.parent_jobs,
+    )
+}
+
+/// 봉인된 IR 버퍼로 Job 제출 (버퍼 PDA/봉인 여부는 온체인에서 검증)
+pub fn submit_job_by_buffer<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SubmitJobByBuffer<'info>>,
+    consumer_program: &Pubkey,
+    args: SubmitJobByBufferArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    let a = &ctx.accounts;
+    require_submit_pdas(
+        &a.config,
+        &a.job,
+        &a.function_record,
+        &a.submitter,
+        a.caller_authority.as_ref(),
+        consumer_program,
+        &args.commitment,
+        args.function_id,
+    )?;
+    crate::cpi::submit_job_by_buffer(
+        ctx,
+        args.commitment,
+        args.da_ptr_hash,
+        args.reveal_after_slot,
+        args.function_id,
+        args.context_data,
+        args.oracle_guard,
+        args.callback,
+        args.parent_jobs,
+    )
+}
+
+/// 등록된 CodeRecord로 Job 제출 - code_record는 digest의 ["code", digest] PDA여야 함
+pub fn submit_job_by_digest<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SubmitJobByDigest<'info>>,
+    consumer_program: &Pubkey,
+    digest: &[u8; 32],
+    args: SubmitJobByDigestArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    let a = &ctx.accounts;
+    require_submit_pdas(
+        &a.config,
+        &a.job,
+        &a.function_record,
+        &a.submitter,
+        a.caller_authority.as_ref(),
+        consumer_program,
+        &args.commitment,
+        args.function_id,
+    )?;
+    require_pda(&a.code_record, pda::code_pda(digest))?;
+    crate::cpi::submit_job_by_digest(
+        ctx,
+        args.commitment,
+        args.da_ptr_hash,
+        args.reveal_after_slot,
+        args.function_id,
+        args.context_data,
+        args.oracle_guard,
+        args.callback,
+        args.parent_jobs,
+    )
+}
+
+/// 챌린지 윈도우가 지난 Job 확정 (콜백은 별도 invoke_callback)
+pub fn finalize<'a, 'b, 'c, 'info>(ctx: CpiContext<'a, 'b, 'c, 'info, Finalize<'info>>) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_pda(&ctx.accounts.config, pda::config_pda())?;
+    require_job_pda(&ctx.accounts.job)?;
+    crate::cpi::finalize(ctx)
+}
+
+/// 확정된 Job의 평문 결과 공개
+pub fn reveal_result<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, RevealResult<'info>>,
+    args: RevealResultArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_job_pda(&ctx.accounts.job)?;
+    crate::cpi::reveal_result(ctx, args.result, args.salt, args.final_step, args.final_registers)
+}
+
+/// 결과 사용 표시 - 소비 프로그램의 ["coproc-caller"] PDA가 signer_seeds로 서명
+pub fn mark_consumed<'a, 'b, 'c, 'info>(ctx: CpiContext<'a, 'b, 'c, 'info, MarkConsumed<'info>>) -> Result<()> {
+    require_program(&ctx.program)?;
+    let job = require_job_pda(&ctx.accounts.job)?;
+    require_pda(&ctx.accounts.consumer, pda::caller_authority_pda(&job.consumer_program))?;
+    crate::cpi::mark_consumed(ctx)
+}
+
+/// 소비 완료된 Job 닫기 - rent는 submitter에게 환불
+/// JobResult로 고정한 Job들은 쓰기 가능한 remaining_accounts로 전달
+pub fn close_job<'a, 'b, 'c, 'info>(ctx: CpiContext<'a, 'b, 'c, 'info, CloseJob<'info>>) -> Result<()> {
+    require_program(&ctx.program)?;
+    let job = require_job_pda(&ctx.accounts.job)?;
+    require_keys_eq!(job.submitter, *ctx.accounts.submitter.key, AnchorError::ConstraintHasOne);
+    crate::cpi::close_job(ctx)
+}
+
+/// 서비스 기한이 지난 미게시 Job 취소
+pub fn cancel_job<'a, 'b, 'c, 'info>(ctx: CpiContext<'a, 'b, 'c, 'info, CancelJob<'info>>) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_pda(&ctx.accounts.config, pda::config_pda())?;
+    let job = require_job_pda(&ctx.accounts.job)?;
+    require_keys_eq!(job.submitter, *ctx.accounts.submitter.key, AnchorError::ConstraintHasOne);
+    crate::cpi::cancel_job(ctx)
+}
+
+/// IR 업로드 버퍼 생성 - ir_buffer는 ["ir-buffer", owner, buffer_id] PDA, owner가 rent 지불
+pub fn init_ir_buffer<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, InitIrBuffer<'info>>,
+    args: InitIrBufferArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_pda(&ctx.accounts.ir_buffer, pda::ir_buffer_pda(ctx.accounts.owner.key, args.buffer_id))?;
+    crate::cpi::init_ir_buffer(ctx, args.buffer_id, args.total_len)
+}
+
+/// IR 청크 순차 기록 (offset = 지금까지 기록한 길이)
+pub fn write_ir_chunk<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, WriteIrBuffer<'info>>,
+    args: WriteIrChunkArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_ir_buffer_pda(&ctx.accounts.ir_buffer, &ctx.accounts.owner)?;
+    crate::cpi::write_ir_chunk(ctx, args.offset, args.chunk)
+}
+
+/// 다 채운 IR 버퍼 봉인 - 이후 submit_job_by_buffer로 제출 가능
+pub fn seal_ir_buffer<'a, 'b, 'c, 'info>(ctx: CpiContext<'a, 'b, 'c, 'info, WriteIrBuffer<'info>>) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_ir_buffer_pda(&ctx.accounts.ir_buffer, &ctx.accounts.owner)?;
+    crate::cpi::seal_ir_buffer(ctx)
+}
+
+/// IR 버퍼 닫기 - rent는 owner에게 환불
+pub fn close_ir_buffer<'a, 'b, 'c, 'info>(ctx: CpiContext<'a, 'b, 'c, 'info, CloseIrBuffer<'info>>) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_ir_buffer_pda(&ctx.accounts.ir_buffer, &ctx.accounts.owner)?;
+    crate::cpi::close_ir_buffer(ctx)
+}
+
+/// 게시된 결과에 이의 제기 - executor_record는 결과를 게시한 실행자의 PDA, 챌린저 스테이크 예치
+pub fn challenge_result<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, ChallengeResult<'info>>,
+    args: ChallengeResultArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_pda(&ctx.accounts.config, pda::config_pda())?;
+    let job = require_job_pda(&ctx.accounts.job)?;
+    let executor = job.executor.ok_or(error!(crate::ErrorCode::InvalidJobStatus))?;
+    require_pda(&ctx.accounts.executor_record, pda::executor_pda(&executor))?;
+    crate::cpi::challenge_result(ctx, args.evidence_hash)
+}
+
+/// 메트릭 커밋먼트에 이의 제기 - executor_record는 커밋한 실행자의 PDA, 챌린저 스테이크 예치
+pub fn challenge_metrics<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, ChallengeMetrics<'info>>,
+    args: ChallengeMetricsArgs,
+) -> Result<()> {
+    require_program(&ctx.program)?;
+    require_pda(&ctx.accounts.config, pda::config_pda())?;
+    let commitment: MetricsCommitment = load(&ctx.accounts.metrics_commitment)?;
+    require_pda(
+        &ctx.accounts.metrics_commitment,
+        pda::metrics_commitment_pda(commitment.window_start_slot),
+    )?;
+    require_pda(&ctx.accounts.executor_record, pda::executor_pda(&commitment.executor))?;
+    crate::cpi::challenge_metrics(ctx, args.evidence_hash)
+}
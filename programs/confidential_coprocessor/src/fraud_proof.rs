@@ -0,0 +1,214 @@
+// programs/confidential_coprocessor/src/fraud_proof.rs
+//! 분쟁 게임용 IR 결정적 재실행
+//! - 레지스터 파일: u64 x NUM_REGISTERS (암호문 핸들을 결정적 값으로 취급)
+//! - 트레이스 리프 i = state_hash(i, i번째 op 실행 후 레지스터)
+//! - step 0 레지스터는 Job에서 유도 (initial_registers), 결과는 마지막 상태의 OUTPUT_REGISTER
+//! - IR 인코딩은 executor/wrapper 포맷과 동일 (lending_demo의 build_*_ir 참고)
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+use crate::ErrorCode;
+
+pub const NUM_REGISTERS: usize = 16;
+pub type Registers = [u64; NUM_REGISTERS];
+pub const OUTPUT_REGISTER: usize = 0;
+
+pub const OP_ADD: u8 = 0x01;
+pub const OP_SUB: u8 = 0x02;
+pub const OP_MUL: u8 = 0x03;
+pub const OP_MUL_CST: u8 = 0x04;
+pub const OP_GTE: u8 = 0x10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IrOp {
+    Add { dst: u8, a: u8, b: u8 },
+    Sub { dst: u8, a: u8, b: u8 },
+    Mul { dst: u8, a: u8, b: u8 },
+    MulCst { dst: u8, src: u8, cst: u32 },
+    Gte { dst: u8, a: u8, b: u8 },
+}
+
+fn reg(byte: u8) -> Result<u8> {
+    require!((byte as usize) < NUM_REGISTERS, ErrorCode::InvalidIrOp);
+    Ok(byte)
+}
+
+/// offset 위치의 op 하나를 디코드 - (op, 인코딩 길이)
+pub fn decode_op(ir: &[u8], offset: usize) -> Result<(IrOp, usize)> {
+    let rest = ir.get(offset..).ok_or(ErrorCode::InvalidIrOp)?;
+    match rest {
+        [OP_MUL_CST, dst, src, _, c0, c1, c2, c3, ..] => Ok((
+            IrOp::MulCst {
+                dst: reg(*dst)?,
+                src: reg(*src)?,
+                cst: u32::from_le_bytes([*c0, *c1, *c2, *c3]),
+            },
+            8,
+        )),
+        [op @ (OP_ADD | OP_SUB | OP_MUL | OP_GTE), dst, a, b, ..] => {
+            let (dst, a, b) = (reg(*dst)?, reg(*a)?, reg(*b)?);
+            let op = match *op {
+                OP_ADD => IrOp::Add { dst, a, b },
+                OP_SUB => IrOp::Sub { dst, a, b },
+                OP_MUL => IrOp::Mul { dst, a, b },
+                _ => IrOp::Gte { dst, a, b },
+            };
+            Ok((op, 4))
+        }
+        _ => err!(ErrorCode::InvalidIrOp),
+    }
+}
+
+/// IR 전체의 op 개수 (끝까지 정확히 디코드되어야 함)
+pub fn count_ops(ir: &[u8]) -> Result<u32> {
+    let mut offset = 0;
+    let mut count = 0u32;
+    while offset < ir.len() {
+        let (_, len) = decode_op(ir, offset)?;
+        offset += len;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// index번째 op
+pub fn op_at(ir: &[u8], index: u32) -> Result<IrOp> {
+    let mut offset = 0;
+    for _ in 0..index {
+        let (_, len) = decode_op(ir, offset)?;
+        offset += len;
+    }
+    Ok(decode_op(ir, offset)?.0)
+}
+
+/// op 하나 실행 (wrapping 산술, GTE는 0/1)
+pub fn execute(regs: &Registers, op: IrOp) -> Registers {
+    let mut out = *regs;
+    let r = |i: u8| regs[i as usize];
+    match op {
+        IrOp::Add { dst, a, b } => out[dst as usize] = r(a).wrapping_add(r(b)),
+        IrOp::Sub { dst, a, b } => out[dst as usize] = r(a).wrapping_sub(r(b)),
+        IrOp::Mul { dst, a, b } => out[dst as usize] = r(a).wrapping_mul(r(b)),
+        IrOp::MulCst { dst, src, cst } => out[dst as usize] = r(src).wrapping_mul(cst as u64),
+        IrOp::Gte { dst, a, b } => out[dst as usize] = (r(a) >= r(b)) as u64,
+    }
+    out
+}
+
+/// step번째 트레이스 리프
+pub fn state_hash(step: u32, regs: &Registers) -> [u8; 32] {
+    let words: Vec<[u8; 8]> = regs.iter().map(|v| v.to_le_bytes()).collect();
+    let mut parts: Vec<&[u8]> = Vec::with_capacity(2 + NUM_REGISTERS);
+    parts.push(b"ir-state-v1");
+    let step_le = step.to_le_bytes();
+    parts.push(&step_le);
+    parts.extend(words.iter().map(|w| w.as_slice()));
+    hashv(&parts).to_bytes()
+}
+
+/// step 0 레지스터 - Job의 입력 커밋을 u64(le) 단위로 적재 (실행자가 고를 수 없음)
+/// r0 = 출력(0), r1..=r4 = commitment, r5..=r8 = context_data, r9..=r12 = 외부 입력 루트
+pub fn initial_registers(commitment: &[u8; 32], context_data: &[u8; 32], inputs_root: &[u8; 32]) -> Registers {
+    let mut regs = [0u64; NUM_REGISTERS];
+    let words = commitment.chunks(8).chain(context_data.chunks(8)).chain(inputs_root.chunks(8));
+    for (reg, word) in regs[OUTPUT_REGISTER + 1..].iter_mut().zip(words) {
+        *reg = u64::from_le_bytes(word.try_into().unwrap());
+    }
+    regs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ADD r0 = r1 + r2 ; MUL_CST r3 = r0 * 3 ; SUB r4 = r3 - r1 ; MUL r5 = r4 * r2 ; GTE r6 = r5 >= r3
+    const IR: [u8; 24] = [
+        0x01, 0x00, 0x01, 0x02, //
+        0x04, 0x03, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, //
+        0x02, 0x04, 0x03, 0x01, //
+        0x03, 0x05, 0x04, 0x02, //
+        0x10, 0x06, 0x05, 0x03,
+    ];
+
+    fn regs(values: &[(usize, u64)]) -> Registers {
+        let mut r = [0u64; NUM_REGISTERS];
+        for (i, v) in values {
+            r[*i] = *v;
+        }
+        r
+    }
+
+    #[test]
+    fn decodes_every_op_with_its_length() {
+        assert_eq!(decode_op(&IR, 0).unwrap(), (IrOp::Add { dst: 0, a: 1, b: 2 }, 4));
+        assert_eq!(decode_op(&IR, 4).unwrap(), (IrOp::MulCst { dst: 3, src: 0, cst: 3 }, 8));
+        assert_eq!(decode_op(&IR, 12).unwrap(), (IrOp::Sub { dst: 4, a: 3, b: 1 }, 4));
+        assert_eq!(decode_op(&IR, 16).unwrap(), (IrOp::Mul { dst: 5, a: 4, b: 2 }, 4));
+        assert_eq!(decode_op(&IR, 20).unwrap(), (IrOp::Gte { dst: 6, a: 5, b: 3 }, 4));
+    }
+
+    #[test]
+    fn rejects_unknown_truncated_and_out_of_range_ops() {
+        assert!(decode_op(&[0x7f, 0, 0, 0], 0).is_err());
+        assert!(decode_op(&[OP_ADD, 0, 1], 0).is_err());
+        assert!(decode_op(&[OP_MUL_CST, 0, 1, 0, 2, 0], 0).is_err());
+        assert!(decode_op(&[OP_ADD, NUM_REGISTERS as u8, 0, 1], 0).is_err());
+        assert!(decode_op(&IR, IR.len() + 1).is_err());
+        assert!(count_ops(&IR[..IR.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn counts_and_indexes_mixed_width_ops() {
+        assert_eq!(count_ops(&IR).unwrap(), 5);
+        assert_eq!(count_ops(&[]).unwrap(), 0);
+        assert_eq!(op_at(&IR, 1).unwrap(), IrOp::MulCst { dst: 3, src: 0, cst: 3 });
+        assert_eq!(op_at(&IR, 4).unwrap(), IrOp::Gte { dst: 6, a: 5, b: 3 });
+        assert!(op_at(&IR, 5).is_err());
+    }
+
+    #[test]
+    fn executes_each_op() {
+        let pre = regs(&[(1, 5), (2, 7)]);
+        let post = execute(&pre, IrOp::Add { dst: 0, a: 1, b: 2 });
+        assert_eq!(post, regs(&[(0, 12), (1, 5), (2, 7)]));
+        assert_eq!(execute(&pre, IrOp::Sub { dst: 0, a: 2, b: 1 })[0], 2);
+        assert_eq!(execute(&pre, IrOp::Sub { dst: 0, a: 1, b: 2 })[0], 5u64.wrapping_sub(7));
+        assert_eq!(execute(&pre, IrOp::Mul { dst: 0, a: 1, b: 2 })[0], 35);
+        assert_eq!(execute(&pre, IrOp::MulCst { dst: 0, src: 2, cst: 1_000 })[0], 7_000);
+        assert_eq!(execute(&pre, IrOp::Gte { dst: 0, a: 2, b: 1 })[0], 1);
+        assert_eq!(execute(&pre, IrOp::Gte { dst: 0, a: 1, b: 2 })[0], 0);
+        assert_eq!(execute(&pre, IrOp::Gte { dst: 0, a: 1, b: 1 })[0], 1);
+    }
+
+    #[test]
+    fn replays_whole_program_step_by_step() {
+        let mut r = regs(&[(1, 5), (2, 7)]);
+        for i in 0..count_ops(&IR).unwrap() {
+            r = execute(&r, op_at(&IR, i).unwrap());
+        }
+        assert_eq!(r, regs(&[(0, 12), (1, 5), (2, 7), (3, 36), (4, 31), (5, 217), (6, 1)]));
+    }
+
+    #[test]
+    fn state_hash_binds_step_and_registers() {
+        let r = regs(&[(1, 5)]);
+        assert_eq!(state_hash(3, &r), state_hash(3, &r));
+        assert_ne!(state_hash(3, &r), state_hash(4, &r));
+        assert_ne!(state_hash(3, &r), state_hash(3, &regs(&[(1, 6)])));
+        assert_ne!(state_hash(0, &r), state_hash(0, &regs(&[(2, 5)])));
+    }
+
+    #[test]
+    fn initial_registers_load_job_inputs_after_the_output_register() {
+        let mut commitment = [0u8; 32];
+        commitment[..8].copy_from_slice(&5u64.to_le_bytes());
+        commitment[8..16].copy_from_slice(&7u64.to_le_bytes());
+        let r = initial_registers(&commitment, &[1; 32], &[2; 32]);
+        assert_eq!(r[OUTPUT_REGISTER], 0);
+        assert_eq!((r[1], r[2], r[3], r[4]), (5, 7, 0, 0));
+        assert!(r[5..9].iter().all(|v| *v == u64::from_le_bytes([1; 8])));
+        assert!(r[9..13].iter().all(|v| *v == u64::from_le_bytes([2; 8])));
+        assert_eq!(&r[13..], &[0; 3]);
+        assert_ne!(r, initial_registers(&commitment, &[1; 32], &[3; 32]));
+    }
+}
@@ -0,0 +1,158 @@
+// programs/confidential_coprocessor/src/inputs.rs
+//! 외부 입력 바인딩 - post_result의 external_ptr_hash = merkle_root(입력 리프들)
+//! - OracleSnapshot: 스냅샷의 ptr_hash (입력 1개면 root = ptr_hash, 기존 포맷과 동일)
+//!   AggregatedSnapshot도 같은 자리에 허용 (oracle-agg-v1 해시, guard는 TWAP/마지막 샘플 슬롯 기준)
+//!   Job에 oracle_guard가 있으면 관측 슬롯이 제출 슬롯 ± staleness 한도 안인지와 신뢰구간 폭도 검사
+//! - OracleBundle: 번들의 ptr_hash (피드별 oracle_ptr_hash의 머클 루트), guard는 피드마다 적용
+//! - JobResult: keccak("job-input-v1" || job || result_commitment), 참조 Job은 Finalized/Revealed
+//!   submit의 parent_jobs도 고정된 JobResult 입력으로 job.inputs 끝에 추가됨
+//! - AccountState: keccak("account-state-v1" || key || owner || sha256(data)), 게시 시점 상태
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::keccak::hashv;
+
+use crate::merkle::merkle_root;
+use crate::{AggregatedSnapshot, ErrorCode, InputKind, Job, JobStatus, OracleBundle, OracleGuard, OracleSnapshot};
+
+pub fn job_result_leaf(job: &Pubkey, result_commitment: &[u8; 32]) -> [u8; 32] {
+    hashv(&[b"job-input-v1", job.as_ref(), result_commitment]).to_bytes()
+}
+
+pub fn account_state_leaf(key: &Pubkey, owner: &Pubkey, data: &[u8]) -> [u8; 32] {
+    hashv(&[
+        b"account-state-v1",
+        key.as_ref(),
+        owner.as_ref(),
+        &hash(data).to_bytes(),
+    ]).to_bytes()
+}
+
+/// 관측 슬롯이 제출 슬롯 ± staleness 한도 안이고 conf/|price| <= conf_band_bp 인지
+pub fn check_oracle_guard(
+    guard: &OracleGuard,
+    submitted_slot: u64,
+    price_e9: i64,
+    conf_e9: u64,
+    observed_slot: u64,
+) -> Result<()> {
+    require!(
+        observed_slot <= submitted_slot.saturating_add(guard.staleness_limit_slots),
+        ErrorCode::OracleSnapshotFromFuture
+    );
+    require!(
+        submitted_slot.abs_diff(observed_slot) <= guard.staleness_limit_slots,
+        ErrorCode::OracleSnapshotStale
+    );
+    require!(
+        conf_e9 as u128 * 10_000 <= price_e9.unsigned_abs() as u128 * guard.conf_band_bp as u128,
+        ErrorCode::OracleConfidenceTooWide
+    );
+    Ok(())
+}
+
+/// 입력 계정 하나의 리프 해시
+pub fn input_leaf(job: &Job, kind: InputKind, info: &AccountInfo) -> Result<[u8; 32]> {
+    let data = info.try_borrow_data()?;
+    match kind {
+        InputKind::OracleSnapshot if data.starts_with(AggregatedSnapshot::DISCRIMINATOR) => {
+            require!(info.owner == &crate::ID, ErrorCode::ExternalInputMismatch);
+            let agg = AggregatedSnapshot::try_deserialize(&mut &data[..])
+                .map_err(|_| error!(ErrorCode::ExternalInputMismatch))?;
+            if let Some(guard) = &job.oracle_guard {
+                check_oracle_guard(guard, job.submitted_slot, agg.twap_e9, agg.conf_e9, agg.observed_slot)?;
+            }
+            Ok(agg.ptr_hash)
+        }
+        InputKind::OracleSnapshot => {
+            require!(info.owner == &crate::ID, ErrorCode::ExternalInputMismatch);
+            let snap = OracleSnapshot::try_deserialize(&mut &data[..])
+                .map_err(|_| error!(ErrorCode::ExternalInputMismatch))?;
+            if let Some(guard) = &job.oracle_guard {
+                check_oracle_guard(guard, job.submitted_slot, snap.price_e9, snap.conf_e9, snap.observed_slot)?;
+            }
+            Ok(snap.ptr_hash)
+        }
+        InputKind::OracleBundle => {
+            require!(info.owner == &crate::ID, ErrorCode::ExternalInputMismatch);
+            let bundle = OracleBundle::try_deserialize(&mut &data[..])
+                .map_err(|_| error!(ErrorCode::ExternalInputMismatch))?;
+            if let Some(guard) = &job.oracle_guard {
+                for e in &bundle.entries {
+                    check_oracle_guard(guard, job.submitted_slot, e.price_e9, e.conf_e9, e.observed_slot)?;
+                }
+            }
+            Ok(bundle.ptr_hash)
+        }
+        InputKind::JobResult => {
+            require!(info.owner == &crate::ID, ErrorCode::ExternalInputMismatch);
+            let job = Job::try_deserialize(&mut &data[..])
+                .map_err(|_| error!(ErrorCode::ExternalInputMismatch))?;
+            require!(
+                matches!(job.status, JobStatus::Finalized | JobStatus::Revealed),
+                ErrorCode::InputJobNotFinalized
+            );
+            let committed = job.result_commitment.ok_or(ErrorCode::MissingResultCommitment)?;
+            Ok(job_result_leaf(info.key, &committed))
+        }
+        InputKind::AccountState => Ok(account_state_leaf(info.key, info.owner, &data)),
+    }
+}
+
+/// Job에 바인딩된 입력들의 머클 루트 - accounts[i]가 job.inputs[i]에 대응
+pub fn inputs_root(job: &Job, accounts: &[AccountInfo]) -> Result<[u8; 32]> {
+    require!(accounts.len() >= job.inputs.len(), ErrorCode::MissingExternalInput);
+
+    let mut leaves = Vec::with_capacity(job.inputs.len());
+    for (input, info) in job.inputs.iter().zip(accounts) {
+        if let Some(pinned) = input.pinned {
+            require_keys_eq!(info.key(), pinned, ErrorCode::PinnedInputMismatch);
+        }
+        leaves.push(input_leaf(job, input.kind, info)?);
+    }
+    Ok(merkle_root(&leaves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUARD: OracleGuard = OracleGuard { staleness_limit_slots: 100, conf_band_bp: 50 };
+
+    fn code(r: Result<()>) -> Option<u32> {
+        match r {
+            Ok(()) => None,
+            Err(anchor_lang::error::Error::AnchorError(e)) => Some(e.error_code_number),
+            Err(e) => panic!("unexpected error {e:?}"),
+        }
+    }
+
+    #[test]
+    fn staleness_window_is_two_sided() {
+        let price = 25_000_000_000;
+        assert_eq!(code(check_oracle_guard(&GUARD, 1_000, price, 0, 900)), None);
+        assert_eq!(code(check_oracle_guard(&GUARD, 1_000, price, 0, 1_100)), None);
+        assert_eq!(
+            code(check_oracle_guard(&GUARD, 1_000, price, 0, 899)),
+            Some(ErrorCode::OracleSnapshotStale.into())
+        );
+        assert_eq!(
+            code(check_oracle_guard(&GUARD, 1_000, price, 0, 1_101)),
+            Some(ErrorCode::OracleSnapshotFromFuture.into())
+        );
+        assert_eq!(
+            code(check_oracle_guard(&GUARD, u64::MAX, price, 0, 0)),
+            Some(ErrorCode::OracleSnapshotStale.into())
+        );
+    }
+
+    #[test]
+    fn confidence_band_is_relative_to_price() {
+        // 0.05 / 25 = 20 bp
+        assert_eq!(code(check_oracle_guard(&GUARD, 0, 25_000_000_000, 50_000_000, 0)), None);
+        assert_eq!(code(check_oracle_guard(&GUARD, 0, -25_000_000_000, 50_000_000, 0)), None);
+        assert_eq!(
+            code(check_oracle_guard(&GUARD, 0, 25_000_000_000, 200_000_000, 0)),
+            Some(ErrorCode::OracleConfidenceTooWide.into())
+        );
+    }
+}
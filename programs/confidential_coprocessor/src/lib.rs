@@ -1,24 +1,328 @@
 // programs/confidential_coprocessor/src/lib.rs
+// Anchor가 생성하는 cpi::* 함수는 핸들러 인자를 그대로 받으므로 크레이트 단위로 허용
+#![allow(clippy::too_many_arguments)]
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
 use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::system_program;
+
+pub mod attestation;
+pub mod callback;
+#[cfg(feature = "cpi")]
+pub mod cpi_client;
+pub mod fraud_proof;
+pub mod inputs;
+pub mod merkle;
+pub mod metrics;
+pub mod oracle;
+
+use fraud_proof::Registers;
 
 declare_id!("CCxx3Q6jHtuXDndGJ5xHndGmA9v5YZoAQN7rSK6GQX9S");
 
+// 소비자와 공유하는 계정 레이아웃/시드는 coprocessor-interface가 원본
+pub use coprocessor_interface::pda::{
+    CALLER_SEED, CODE_SEED, CONFIG_SEED, DISPUTE_SEED, EXECUTOR_SEED, FUNCTION_SEED,
+    IR_BUFFER_SEED, JOB_SEED, METRICS_COMMIT_SEED, METRICS_SEED, ORACLE_AGG_SEED,
+    ORACLE_BUNDLE_SEED, ORACLE_SNAPSHOT_SEED,
+};
+pub use coprocessor_interface::{
+    oracle_ptr_hash, AggregatedSnapshot, BundleEntry, CallbackSpec, CallbackTrigger, CodeRecord,
+    Config, Dispute, DisputePhase, ExecutorRecord, ExecutorStatus, FunctionRecord, InputKind,
+    IrBuffer, Job, JobInput, JobStatus, MetricsCommitment, MetricsHistory, MetricsStatus,
+    MetricsWindow, OracleBundle, OracleGuard, OracleSnapshot, TraceCommitment,
+    MAX_AGGREGATE_SAMPLES, MAX_ALLOWED_DIGESTS, MAX_BUFFERED_IR_BYTES, MAX_BUNDLE_FEEDS,
+    MAX_CODE_NAME_LEN, MAX_COMMITTEE, MAX_PAUSED_FUNCTIONS, MAX_REQUIRED_INPUTS,
+    METRICS_HISTORY_LEN,
+};
+
 #[program]
 pub mod conf_coprocessor {
     use super::*;
 
-    /// 권한/실행자/챌린지 윈도우 초기화
+    /// 권한/챌린지 윈도우/본드 파라미터 초기화 (실행자는 add_executor로 등록)
     pub fn initialize_config(
         ctx: Context<InitializeConfig>,
-        executor: Pubkey,
         challenge_window_slots: u64,
+        min_bond_lamports: u64,
+        challenger_reward_bp: u16,
+        treasury: Pubkey,
+        service_deadline_slots: u64,
+        snapshot_retention_slots: u64,
+        challenger_stake_lamports: u64,
+        max_bundle_slot_spread: u64,
     ) -> Result<()> {
+        require!(challenger_reward_bp <= 10_000, ErrorCode::InvalidBasisPoints);
+
         let config = &mut ctx.accounts.config;
         config.authority = ctx.accounts.authority.key();
-        config.executor = executor;
         config.challenge_window_slots = challenge_window_slots;
+        config.min_bond_lamports = min_bond_lamports;
+        config.challenger_reward_bp = challenger_reward_bp;
+        config.treasury = treasury;
+        config.service_deadline_slots = service_deadline_slots;
+        config.snapshot_retention_slots = snapshot_retention_slots;
+        config.committee = Vec::new();
+        config.attestation_threshold = 0;
+        config.pending_authority = None;
+        config.paused = false;
+        config.paused_functions = Vec::new();
         config.bump = ctx.bumps.config;
+        config.challenger_stake_lamports = challenger_stake_lamports;
+        config.max_bundle_slot_spread = max_bundle_slot_spread;
+        Ok(())
+    }
+
+    /// 설정 변경 - authority 전용, None인 항목은 유지
+    /// (실행자 키 교체는 add_executor / remove_executor로)
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        challenge_window_slots: Option<u64>,
+        min_bond_lamports: Option<u64>,
+        challenger_reward_bp: Option<u16>,
+        treasury: Option<Pubkey>,
+        service_deadline_slots: Option<u64>,
+        snapshot_retention_slots: Option<u64>,
+        challenger_stake_lamports: Option<u64>,
+        max_bundle_slot_spread: Option<u64>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        if let Some(window) = challenge_window_slots {
+            config.challenge_window_slots = window;
+        }
+        if let Some(min_bond) = min_bond_lamports {
+            config.min_bond_lamports = min_bond;
+        }
+        if let Some(reward_bp) = challenger_reward_bp {
+            require!(reward_bp <= 10_000, ErrorCode::InvalidBasisPoints);
+            config.challenger_reward_bp = reward_bp;
+        }
+        if let Some(treasury) = treasury {
+            config.treasury = treasury;
+        }
+        if let Some(deadline) = service_deadline_slots {
+            config.service_deadline_slots = deadline;
+        }
+        if let Some(retention) = snapshot_retention_slots {
+            config.snapshot_retention_slots = retention;
+        }
+        if let Some(stake) = challenger_stake_lamports {
+            config.challenger_stake_lamports = stake;
+        }
+        if let Some(spread) = max_bundle_slot_spread {
+            config.max_bundle_slot_spread = spread;
+        }
+
+        emit!(ConfigUpdated {
+            challenge_window_slots: config.challenge_window_slots,
+            min_bond_lamports: config.min_bond_lamports,
+            challenger_reward_bp: config.challenger_reward_bp,
+            treasury: config.treasury,
+            service_deadline_slots: config.service_deadline_slots,
+            snapshot_retention_slots: config.snapshot_retention_slots,
+            challenger_stake_lamports: config.challenger_stake_lamports,
+            max_bundle_slot_spread: config.max_bundle_slot_spread,
+        });
+        Ok(())
+    }
+
+    /// authority 이전 1단계 - 현재 authority가 후보 지정 (재호출 시 덮어씀)
+    pub fn propose_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_authority = Some(new_authority);
+
+        emit!(AuthorityProposed { authority: config.authority, pending_authority: new_authority });
+        Ok(())
+    }
+
+    /// authority 이전 2단계 - 후보가 직접 서명해서 수락
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous = config.authority;
+
+        config.authority = ctx.accounts.pending_authority.key();
+        config.pending_authority = None;
+
+        emit!(AuthorityAccepted { previous_authority: previous, authority: config.authority });
+        Ok(())
+    }
+
+    /// 전역 일시 정지 - authority 전용 (submit/post 차단, finalize/환불은 계속 동작)
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.config.paused = paused;
+        emit!(PauseUpdated { paused });
+        Ok(())
+    }
+
+    /// function_id 단위 일시 정지 - authority 전용
+    pub fn set_function_paused(ctx: Context<UpdateConfig>, function_id: u16, paused: bool) -> Result<()> {
+        let paused_functions = &mut ctx.accounts.config.paused_functions;
+
+        if paused {
+            if !paused_functions.contains(&function_id) {
+                require!(paused_functions.len() < MAX_PAUSED_FUNCTIONS, ErrorCode::TooManyPausedFunctions);
+                paused_functions.push(function_id);
+            }
+        } else {
+            paused_functions.retain(|f| *f != function_id);
+        }
+
+        emit!(FunctionPauseUpdated { function_id, paused });
+        Ok(())
+    }
+
+    /// 위원회 설정 - authority 전용
+    /// threshold > 0이면 post_result에 위원회 M-of-N Ed25519 서명이 필요 (0 = 단일 실행자 모드)
+    pub fn set_committee(ctx: Context<SetCommittee>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(members.len() <= MAX_COMMITTEE, ErrorCode::CommitteeTooLarge);
+        require!((threshold as usize) <= members.len(), ErrorCode::InvalidThreshold);
+        for (i, member) in members.iter().enumerate() {
+            require!(!members[..i].contains(member), ErrorCode::DuplicateCommitteeMember);
+        }
+
+        let config = &mut ctx.accounts.config;
+        config.committee = members.clone();
+        config.attestation_threshold = threshold;
+
+        emit!(CommitteeUpdated { members, threshold });
+        Ok(())
+    }
+
+    /// 함수 등록 - authority 전용, (consumer_program, function_id)당 FunctionRecord PDA 생성
+    /// 이후 수정은 owner가 update_function으로
+    pub fn register_function(
+        ctx: Context<RegisterFunction>,
+        consumer_program: Pubkey,
+        function_id: u16,
+        owner: Pubkey,
+        allowed_digests: Vec<[u8; 32]>,
+        required_inputs: Vec<InputKind>,
+        challenge_window_slots: Option<u64>,
+    ) -> Result<()> {
+        let record = &mut ctx.accounts.function_record;
+        record.consumer_program = consumer_program;
+        record.function_id = function_id;
+        record.owner = owner;
+        record.bump = ctx.bumps.function_record;
+        set_function_spec(record, allowed_digests, required_inputs, challenge_window_slots)?;
+
+        emit!(FunctionRegistered { consumer_program, function_id, owner });
+        emit_function_updated(record);
+        Ok(())
+    }
+
+    /// 함수 스펙 변경 - FunctionRecord owner 전용 (허용 다이제스트 목록은 전체 교체)
+    pub fn update_function(
+        ctx: Context<UpdateFunction>,
+        allowed_digests: Vec<[u8; 32]>,
+        required_inputs: Vec<InputKind>,
+        challenge_window_slots: Option<u64>,
+    ) -> Result<()> {
+        let record = &mut ctx.accounts.function_record;
+        set_function_spec(record, allowed_digests, required_inputs, challenge_window_slots)?;
+        emit_function_updated(record);
+        Ok(())
+    }
+
+    /// 실행자 등록 - authority 전용, 실행자별 ExecutorRecord PDA 생성
+    pub fn add_executor(ctx: Context<AddExecutor>, executor: Pubkey) -> Result<()> {
+        let record = &mut ctx.accounts.executor_record;
+        let clock = Clock::get()?;
+
+        record.executor = executor;
+        record.status = ExecutorStatus::Active;
+        record.added_slot = clock.slot;
+        record.bond_lamports = 0;
+        record.open_challenges = 0;
+        record.last_posted_slot = 0;
+        record.bond_locked_until_slot = 0;
+        record.bump = ctx.bumps.executor_record;
+
+        emit!(ExecutorAdded { executor, slot: clock.slot });
+        Ok(())
+    }
+
+    /// 실행자 일시 정지/재개 - authority 전용
+    pub fn set_executor_suspended(ctx: Context<UpdateExecutor>, suspended: bool) -> Result<()> {
+        let record = &mut ctx.accounts.executor_record;
+        let clock = Clock::get()?;
+
+        record.status = if suspended { ExecutorStatus::Suspended } else { ExecutorStatus::Active };
+
+        emit!(ExecutorStatusChanged {
+            executor: record.executor,
+            status: record.status.clone(),
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// 실행자 제거 - authority 전용
+    /// 미해결 챌린지가 없고 게시한 Job들의 챌린지 윈도우가 모두 지나야 함
+    /// 본드는 실행자에게, 레코드 rent는 authority에게 반환
+    pub fn remove_executor(ctx: Context<RemoveExecutor>) -> Result<()> {
+        let record = &mut ctx.accounts.executor_record;
+        let clock = Clock::get()?;
+
+        require!(record.open_challenges == 0, ErrorCode::OpenChallengesPending);
+        require!(
+            clock.slot >= record.bond_locked_until_slot,
+            ErrorCode::ChallengeWindowNotPassed
+        );
+
+        let bond = record.bond_lamports;
+        record.bond_lamports = 0;
+        **record.to_account_info().try_borrow_mut_lamports()? -= bond;
+        **ctx.accounts.executor.try_borrow_mut_lamports()? += bond;
+
+        emit!(ExecutorRemoved { executor: record.executor, slot: clock.slot });
+        Ok(())
+    }
+
+    /// 실행자 본드 예치 (SOL) - ExecutorRecord PDA가 에스크로
+    pub fn deposit_bond(ctx: Context<DepositBond>, amount: u64) -> Result<()> {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.executor.to_account_info(),
+                    to: ctx.accounts.executor_record.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let record = &mut ctx.accounts.executor_record;
+        record.bond_lamports += amount;
+
+        emit!(BondDeposited { executor: record.executor, amount, bond_lamports: record.bond_lamports });
+        Ok(())
+    }
+
+    /// 실행자 본드 인출
+    /// 미해결 챌린지가 없어야 하며, 활성 실행자는 min_bond_lamports 이상을 남겨야 함
+    /// (전액 인출은 정지 상태 + 게시한 Job들의 챌린지 윈도우 경과 시에만)
+    pub fn withdraw_bond(ctx: Context<WithdrawBond>, amount: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let record = &mut ctx.accounts.executor_record;
+        let clock = Clock::get()?;
+
+        require!(record.open_challenges == 0, ErrorCode::OpenChallengesPending);
+        let remaining = record.bond_lamports.checked_sub(amount).ok_or(ErrorCode::InsufficientBond)?;
+        if remaining < config.min_bond_lamports {
+            require!(record.status == ExecutorStatus::Suspended, ErrorCode::InsufficientBond);
+            require!(
+                clock.slot >= record.bond_locked_until_slot,
+                ErrorCode::ChallengeWindowNotPassed
+            );
+        }
+
+        record.bond_lamports = remaining;
+        **record.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.executor.try_borrow_mut_lamports()? += amount;
+
+        emit!(BondWithdrawn { executor: record.executor, amount, bond_lamports: remaining });
         Ok(())
     }
 
@@ -31,10 +335,10 @@ pub mod conf_coprocessor {
         function_id: u16,
         context_data: [u8; 32],
         ir_bytes: Vec<u8>,
+        oracle_guard: Option<OracleGuard>,
+        callback: Option<CallbackSpec>,
+        parent_jobs: Vec<Pubkey>,
     ) -> Result<()> {
-        let job = &mut ctx.accounts.job;
-        let clock = Clock::get()?;
-
         // 크기 제한으로 DoS 방어 (2KB 제한)
         const MAX_IR_BYTES: usize = 2048;
         require!(ir_bytes.len() <= MAX_IR_BYTES, ErrorCode::IrTooLarge);
@@ -42,101 +346,705 @@ pub mod conf_coprocessor {
         // 온체인에서 IR 해시 계산
         let digest = anchor_lang::solana_program::hash::hash(&ir_bytes).to_bytes();
 
-        // Job 초기화
-        job.commitment = commitment;
-        job.da_ptr_hash = da_ptr_hash;
-        job.expected_code_digest = digest;
-        job.result_commitment = None;
-        job.external_ptr_hash = None;
-        job.status = JobStatus::Submitted;
-        job.posted_slot = None;
-        job.reveal_after_slot = reveal_after_slot;
-        job.function_id = function_id;
-        job.context_data = context_data;
-        job.submitter = ctx.accounts.submitter.key();
-        job.bump = ctx.bumps.job;
+        let spec = JobSpec {
+            commitment,
+            da_ptr_hash,
+            reveal_after_slot,
+            function_id,
+            context_data,
+            code_digest: digest,
+            ir_account: None,
+            oracle_guard,
+            callback,
+            caller_program: caller_program(&ctx.accounts.function_record, ctx.accounts.caller_authority.as_ref())?,
+            parent_jobs,
+        };
+        init_job(
+            &mut ctx.accounts.job,
+            &ctx.accounts.config,
+            &ctx.accounts.function_record,
+            ctx.accounts.submitter.key(),
+            ctx.bumps.job,
+            spec,
+            ctx.remaining_accounts,
+        )
+    }
 
-        emit!(JobSubmitted {
-            job: job.key(),
-            submitter: job.submitter,
+    /// 버퍼 모드 1단계 - total_len 크기의 IR 버퍼 생성 (여러 트랜잭션에 걸쳐 업로드)
+    pub fn init_ir_buffer(ctx: Context<InitIrBuffer>, buffer_id: u64, total_len: u32) -> Result<()> {
+        require!(
+            total_len > 0 && total_len as usize <= MAX_BUFFERED_IR_BYTES,
+            ErrorCode::IrTooLarge
+        );
+
+        let buffer = &mut ctx.accounts.ir_buffer;
+        buffer.owner = ctx.accounts.owner.key();
+        buffer.buffer_id = buffer_id;
+        buffer.total_len = total_len;
+        buffer.written_len = 0;
+        buffer.sealed = false;
+        buffer.digest = [0u8; 32];
+        buffer.bump = ctx.bumps.ir_buffer;
+        buffer.data = vec![0u8; total_len as usize];
+        Ok(())
+    }
+
+    /// 버퍼 모드 2단계 - 청크 순차 기록 (offset은 지금까지 기록한 길이와 같아야 함)
+    pub fn write_ir_chunk(ctx: Context<WriteIrBuffer>, offset: u32, chunk: Vec<u8>) -> Result<()> {
+        let buffer = &mut ctx.accounts.ir_buffer;
+
+        require!(!buffer.sealed, ErrorCode::IrBufferSealed);
+        require!(offset == buffer.written_len, ErrorCode::IrChunkOutOfOrder);
+        let end = offset
+            .checked_add(chunk.len() as u32)
+            .filter(|end| *end <= buffer.total_len)
+            .ok_or(ErrorCode::IrTooLarge)?;
+
+        buffer.data[offset as usize..end as usize].copy_from_slice(&chunk);
+        buffer.written_len = end;
+        Ok(())
+    }
+
+    /// 버퍼 모드 3단계 - 전체 기록 확인 후 다이제스트 계산, 이후 수정 불가
+    pub fn seal_ir_buffer(ctx: Context<WriteIrBuffer>) -> Result<()> {
+        let buffer = &mut ctx.accounts.ir_buffer;
+
+        require!(!buffer.sealed, ErrorCode::IrBufferSealed);
+        require!(buffer.written_len == buffer.total_len, ErrorCode::IrBufferIncomplete);
+
+        buffer.digest = anchor_lang::solana_program::hash::hash(&buffer.data).to_bytes();
+        buffer.sealed = true;
+
+        emit!(IrBufferSealed {
+            ir_buffer: buffer.key(),
+            owner: buffer.owner,
+            total_len: buffer.total_len,
+            digest: buffer.digest,
+        });
+        Ok(())
+    }
+
+    /// 버퍼 모드 Job 제출 - 봉인된 IR 버퍼의 다이제스트를 사용
+    pub fn submit_job_by_buffer(
+        ctx: Context<SubmitJobByBuffer>,
+        commitment: [u8; 32],
+        da_ptr_hash: Option<[u8; 32]>,
+        reveal_after_slot: u64,
+        function_id: u16,
+        context_data: [u8; 32],
+        oracle_guard: Option<OracleGuard>,
+        callback: Option<CallbackSpec>,
+        parent_jobs: Vec<Pubkey>,
+    ) -> Result<()> {
+        let buffer = &ctx.accounts.ir_buffer;
+        let spec = JobSpec {
             commitment,
             da_ptr_hash,
-            expected_code_digest: digest,
+            reveal_after_slot,
             function_id,
             context_data,
+            code_digest: buffer.digest,
+            ir_account: Some(buffer.key()),
+            oracle_guard,
+            callback,
+            caller_program: caller_program(&ctx.accounts.function_record, ctx.accounts.caller_authority.as_ref())?,
+            parent_jobs,
+        };
+        init_job(
+            &mut ctx.accounts.job,
+            &ctx.accounts.config,
+            &ctx.accounts.function_record,
+            ctx.accounts.submitter.key(),
+            ctx.bumps.job,
+            spec,
+            ctx.remaining_accounts,
+        )
+    }
+
+    /// IR 버퍼 닫기 - owner에게 rent 환불 (Job에는 다이제스트만 남음)
+    pub fn close_ir_buffer(_ctx: Context<CloseIrBuffer>) -> Result<()> {
+        Ok(())
+    }
+
+    /// 코드 레지스트리 등록 - authority 전용, 다이제스트당 1회
+    /// ir_bytes가 있으면 다이제스트/op 개수를 온체인 검증, 없으면 DA 포인터 필수
+    pub fn register_code(
+        ctx: Context<RegisterCode>,
+        digest: [u8; 32],
+        ir_bytes: Option<Vec<u8>>,
+        da_ptr_hash: Option<[u8; 32]>,
+        name: String,
+        version: u16,
+        op_count: u32,
+    ) -> Result<()> {
+        require!(name.len() <= MAX_CODE_NAME_LEN, ErrorCode::CodeNameTooLong);
+        require!(ir_bytes.is_some() || da_ptr_hash.is_some(), ErrorCode::MissingCodeSource);
+        if let Some(ir) = &ir_bytes {
+            require!(
+                anchor_lang::solana_program::hash::hash(ir).to_bytes() == digest,
+                ErrorCode::CodeDigestMismatch
+            );
+            require!(fraud_proof::count_ops(ir)? == op_count, ErrorCode::OpCountMismatch);
+        }
+
+        let clock = Clock::get()?;
+        let record = &mut ctx.accounts.code_record;
+        record.digest = digest;
+        record.registrar = ctx.accounts.authority.key();
+        record.name = name.clone();
+        record.version = version;
+        record.op_count = op_count;
+        record.da_ptr_hash = da_ptr_hash;
+        record.registered_slot = clock.slot;
+        record.bump = ctx.bumps.code_record;
+        record.ir = ir_bytes.unwrap_or_default();
+
+        emit!(CodeRegistered {
+            code_record: record.key(),
+            digest,
+            name,
+            version,
+            op_count,
+            da_ptr_hash,
             slot: clock.slot,
         });
         Ok(())
     }
 
+    /// 다이제스트 참조 모드 Job 제출 - 등록된 CodeRecord만 참조 (IR 재전송/재해시 없음)
+    pub fn submit_job_by_digest(
+        ctx: Context<SubmitJobByDigest>,
+        commitment: [u8; 32],
+        da_ptr_hash: Option<[u8; 32]>,
+        reveal_after_slot: u64,
+        function_id: u16,
+        context_data: [u8; 32],
+        oracle_guard: Option<OracleGuard>,
+        callback: Option<CallbackSpec>,
+        parent_jobs: Vec<Pubkey>,
+    ) -> Result<()> {
+        let code = &ctx.accounts.code_record;
+        let spec = JobSpec {
+            commitment,
+            da_ptr_hash,
+            reveal_after_slot,
+            function_id,
+            context_data,
+            code_digest: code.digest,
+            ir_account: Some(code.key()),
+            oracle_guard,
+            callback,
+            caller_program: caller_program(&ctx.accounts.function_record, ctx.accounts.caller_authority.as_ref())?,
+            parent_jobs,
+        };
+        init_job(
+            &mut ctx.accounts.job,
+            &ctx.accounts.config,
+            &ctx.accounts.function_record,
+            ctx.accounts.submitter.key(),
+            ctx.bumps.job,
+            spec,
+            ctx.remaining_accounts,
+        )
+    }
+
 
     /// 실행자가 결정적 결과 커밋을 게시
+    /// 트레이스 초기 상태는 Job의 commitment/context_data/입력 루트에서 유도하고 최종 상태만 실행자가 커밋
     pub fn post_result(
         ctx: Context<PostResult>,
         result_commitment: [u8; 32],
         code_digest_again: [u8; 32],
         external_ptr_hash: Option<[u8; 32]>,
+        final_state_hash: [u8; 32],
     ) -> Result<()> {
         let job = &mut ctx.accounts.job;
         let clock = Clock::get()?;
 
         require!(job.status == JobStatus::Submitted, ErrorCode::InvalidJobStatus);
+        require_active(&ctx.accounts.config, job.function_id)?;
         require!(code_digest_again == job.expected_code_digest, ErrorCode::CodeDigestMismatch);
+        require!(
+            ctx.accounts.executor_record.bond_lamports >= ctx.accounts.config.min_bond_lamports,
+            ErrorCode::InsufficientBond
+        );
 
-        // LIQ eligibility job이면 snapshot 필수 (FID_LIQ_ELIGIBILITY = 400)
-        if job.function_id == 400 {
-            require!(external_ptr_hash.is_some(), ErrorCode::MissingSnapshotHash);
-            require!(ctx.remaining_accounts.len() >= 1, ErrorCode::MissingSnapshot);
-            
-            // Verify snapshot account owner and deserialize
-            let snap_info = &ctx.remaining_accounts[0];
-            require!(snap_info.owner == &crate::ID, ErrorCode::MissingSnapshot);
-            let snap_data = snap_info.try_borrow_data()?;
-            require!(snap_data.len() >= 8, ErrorCode::MissingSnapshot);
-            let snap = OracleSnapshot::deserialize(&mut &snap_data[8..])?;
-            let eph = external_ptr_hash.unwrap();
-            require!(eph == snap.ptr_hash, ErrorCode::SnapshotHashMismatch);
+        // 위원회 모드: 같은 트랜잭션의 Ed25519 instruction으로 M-of-N 서명 확인
+        let config = &ctx.accounts.config;
+        if config.attestation_threshold > 0 {
+            let message = attestation::post_result_message(&job.key(), &result_commitment, &code_digest_again);
+            let signed = attestation::count_committee_signatures(
+                &ctx.accounts.instructions.to_account_info(),
+                &config.committee,
+                &message,
+            )?;
+            require!(signed >= config.attestation_threshold as usize, ErrorCode::InsufficientAttestations);
+        }
+
+        require_allowed_digest(&ctx.accounts.function_record, &job.expected_code_digest)?;
+
+        // 제출 시 바인딩된 외부 입력 검증 - remaining_accounts[i]가 job.inputs[i]에 대응
+        // oracle_guard가 있으면 스냅샷 입력의 신선도/신뢰구간도 검사
+        // 입력이 없는 Job의 external_ptr_hash는 검증되지 않으므로 초기 상태에 넣지 않음
+        let mut verified_root = [0u8; 32];
+        if !job.inputs.is_empty() {
+            let eph = external_ptr_hash.ok_or(ErrorCode::MissingExternalInput)?;
+            let root = inputs::inputs_root(job, ctx.remaining_accounts)?;
+            require!(eph == root, ErrorCode::ExternalInputMismatch);
+            verified_root = root;
         }
+        let initial_registers = fraud_proof::initial_registers(&job.commitment, &job.context_data, &verified_root);
+        let trace_commitment = TraceCommitment {
+            initial_state_hash: fraud_proof::state_hash(0, &initial_registers),
+            final_state_hash,
+        };
 
         job.result_commitment = Some(result_commitment);
         job.external_ptr_hash = external_ptr_hash;
         job.status = JobStatus::Posted;
         job.posted_slot = Some(clock.slot);
+        job.executor = Some(ctx.accounts.executor.key());
+        job.trace_commitment = Some(trace_commitment);
+        let record = &mut ctx.accounts.executor_record;
+        record.last_posted_slot = clock.slot;
+        record.bond_locked_until_slot = record
+            .bond_locked_until_slot
+            .max(clock.slot + job.challenge_window_slots);
 
         emit!(JobPosted {
             job: job.key(),
+            executor: ctx.accounts.executor.key(),
             result_commitment,
             code_digest: code_digest_again,
             external_ptr_hash,
             posted_slot: clock.slot,
+            trace_commitment,
         });
         Ok(())
     }
 
     /// 짧은 챌린지 윈도우 경과 후 누구나 파이널라이즈
     pub fn finalize(ctx: Context<Finalize>) -> Result<()> {
-        let config = &ctx.accounts.config;
         let job = &mut ctx.accounts.job;
         let clock = Clock::get()?;
 
+        require!(job.status != JobStatus::Challenged, ErrorCode::JobUnderChallenge);
         require!(job.status == JobStatus::Posted, ErrorCode::InvalidJobStatus);
         let posted_slot = job.posted_slot.ok_or(ErrorCode::MissingPostedSlot)?;
-        require!(clock.slot >= posted_slot + config.challenge_window_slots, ErrorCode::ChallengeWindowNotPassed);
+        require!(clock.slot >= posted_slot + job.challenge_window_slots, ErrorCode::ChallengeWindowNotPassed);
 
         job.status = JobStatus::Finalized;
         emit!(JobFinalized { job: job.key(), slot: clock.slot });
         Ok(())
     }
 
-    /// 30초 지표 발행 - executor 전용 (상태 저장 없이 이벤트만)
+    /// 서비스 기한 내 결과가 게시되지 않은 Job 취소 - submitter 전용, 계정 닫고 rent 환불
+    /// (일시 정지 중에도 동작, 취소 사실은 JobCancelled 이벤트로만 남음)
+    /// remaining_accounts = JobResult 입력으로 고정한 Job들 (dependent_jobs 감소)
+    pub fn cancel_job(ctx: Context<CancelJob>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let job = &ctx.accounts.job;
+        let clock = Clock::get()?;
+
+        require!(job.status == JobStatus::Submitted, ErrorCode::InvalidJobStatus);
+        require!(
+            clock.slot >= job.submitted_slot + config.service_deadline_slots,
+            ErrorCode::ServiceDeadlineNotPassed
+        );
+        require!(job.dependent_jobs == 0, ErrorCode::JobHasDependents);
+        update_dependencies(&job.inputs, ctx.remaining_accounts, false)?;
+
+        emit!(JobCancelled {
+            job: job.key(),
+            submitter: job.submitter,
+            function_id: job.function_id,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// reveal_after_slot 이후 평문 결과 공개 - result_commitment와 대조 후 Job에 저장
+    /// 결과는 커밋된 트레이스 최종 상태(final_step, final_registers)의 출력 레지스터여야 함
+    pub fn reveal_result(
+        ctx: Context<RevealResult>,
+        result: u64,
+        salt: [u8; 32],
+        final_step: u32,
+        final_registers: Registers,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let clock = Clock::get()?;
+
+        require!(job.status == JobStatus::Finalized, ErrorCode::InvalidJobStatus);
+        require!(clock.slot >= job.reveal_after_slot, ErrorCode::RevealTooEarly);
+        let committed = job.result_commitment.ok_or(ErrorCode::MissingResultCommitment)?;
+        require!(
+            result_commitment_hash(&job.key(), result, &salt) == committed,
+            ErrorCode::RevealMismatch
+        );
+        let trace = job.trace_commitment.ok_or(ErrorCode::MissingTraceCommitment)?;
+        require!(
+            fraud_proof::state_hash(final_step, &final_registers) == trace.final_state_hash,
+            ErrorCode::FinalStateMismatch
+        );
+        require!(final_registers[fraud_proof::OUTPUT_REGISTER] == result, ErrorCode::RevealMismatch);
+
+        job.revealed_result = Some(result);
+        job.status = JobStatus::Revealed;
+
+        emit!(JobRevealed { job: job.key(), result, slot: clock.slot });
+        Ok(())
+    }
+
+    /// 소비자 콜백 실행 - trigger 상태(Finalize: 확정/공개, Reveal: 공개)에 도달한 Job에 대해 누구나 1회
+    /// finalize/reveal_result와 분리되어 있어 콜백이 실패해도 Job은 확정된 채로 소비 가능
+    /// remaining_accounts[0] = 콜백 프로그램, 나머지는 콜백 계정으로 전달
+    pub fn invoke_callback<'info>(ctx: Context<'_, '_, 'info, 'info, InvokeCallback<'info>>) -> Result<()> {
+        callback::invoke_callback(&mut ctx.accounts.job, ctx.remaining_accounts)
+    }
+
+    /// 소비자가 결과 사용을 표시 - 소비 프로그램의 ["coproc-caller"] PDA 서명 필요
+    /// (consumer_program이 프로그램이 아닌 오프체인 소비자 키면 그 키의 서명)
+    pub fn mark_consumed(ctx: Context<MarkConsumed>) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        let clock = Clock::get()?;
+
+        require!(
+            matches!(job.status, JobStatus::Finalized | JobStatus::Revealed),
+            ErrorCode::InvalidJobStatus
+        );
+        require!(job.consumed_slot.is_none(), ErrorCode::JobAlreadyConsumed);
+
+        job.consumed_slot = Some(clock.slot);
+        emit!(JobConsumed { job: job.key(), submitter: job.submitter, slot: clock.slot });
+        Ok(())
+    }
+
+    /// 소비 완료된 Job 계정 닫기 - rent는 submitter에게 환불
+    /// 이 Job을 입력으로 고정한 Job이 남아 있으면 불가, remaining_accounts = 이 Job이 고정한 Job들
+    pub fn close_job(ctx: Context<CloseJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
+
+        require!(
+            matches!(job.status, JobStatus::Finalized | JobStatus::Revealed),
+            ErrorCode::InvalidJobStatus
+        );
+        require!(job.consumed_slot.is_some(), ErrorCode::JobNotConsumed);
+        require!(job.dependent_jobs == 0, ErrorCode::JobHasDependents);
+        update_dependencies(&job.inputs, ctx.remaining_accounts, false)?;
+
+        emit!(JobClosed { job: job.key(), submitter: job.submitter, slot: Clock::get()?.slot });
+        Ok(())
+    }
+
+    /// 챌린지 윈도우 안에서 게시된 결과에 이의 제기 - 누구나 가능, finalize 차단
+    /// 챌린저는 config.challenger_stake_lamports를 Job 계정에 예치 (기각 시 몰수)
+    pub fn challenge_result(ctx: Context<ChallengeResult>, evidence_hash: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let stake = ctx.accounts.config.challenger_stake_lamports;
+
+        {
+            let job = &ctx.accounts.job;
+            require!(job.status == JobStatus::Posted, ErrorCode::InvalidJobStatus);
+            let posted_slot = job.posted_slot.ok_or(ErrorCode::MissingPostedSlot)?;
+            require!(clock.slot < posted_slot + job.challenge_window_slots, ErrorCode::ChallengeWindowClosed);
+        }
+
+        if stake > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.challenger.to_account_info(),
+                        to: ctx.accounts.job.to_account_info(),
+                    },
+                ),
+                stake,
+            )?;
+        }
+
+        let job = &mut ctx.accounts.job;
+        job.status = JobStatus::Challenged;
+        job.challenger = Some(ctx.accounts.challenger.key());
+        job.challenge_evidence_hash = Some(evidence_hash);
+        job.challenger_stake_lamports = stake;
+        ctx.accounts.executor_record.open_challenges += 1;
+
+        emit!(JobChallenged {
+            job: job.key(),
+            challenger: ctx.accounts.challenger.key(),
+            result_commitment: job.result_commitment,
+            evidence_hash,
+            slot: clock.slot,
+            stake_lamports: stake,
+        });
+        Ok(())
+    }
+
+    /// authority 챌린지 판정
+    /// - executor 과실: 본드 슬래싱, 결과 무효화 후 Submitted로 되돌려 다른 실행자가 재게시
+    /// - 챌린지 기각: Posted로 복귀 (기존 posted_slot 유지)
+    /// 진행 중인 분쟁 게임이 있으면 중단하고 분쟁 계정은 챌린저에게 닫음
+    pub fn resolve_challenge(ctx: Context<ResolveChallenge>, executor_at_fault: bool) -> Result<()> {
+        let clock = Clock::get()?;
+
+        require!(ctx.accounts.job.status == JobStatus::Challenged, ErrorCode::InvalidJobStatus);
+        let dispute_info = ctx.accounts.dispute.to_account_info();
+        if dispute_info.owner == &crate::ID && !dispute_info.data_is_empty() {
+            Dispute::try_deserialize(&mut &dispute_info.try_borrow_data()?[..])?;
+            emit!(DisputeAborted { job: ctx.accounts.job.key(), dispute: dispute_info.key(), slot: clock.slot });
+            let challenger = ctx.accounts.challenger.to_account_info();
+            **challenger.try_borrow_mut_lamports()? += dispute_info.lamports();
+            **dispute_info.try_borrow_mut_lamports()? = 0;
+            dispute_info.assign(&system_program::ID);
+            dispute_info.resize(0)?;
+        }
+        let mut settlement = Settlement {
+            config: &ctx.accounts.config,
+            executor_record: &mut ctx.accounts.executor_record,
+            challenger: ctx.accounts.challenger.to_account_info(),
+            treasury: ctx.accounts.treasury.to_account_info(),
+        };
+        settle_challenge(&mut ctx.accounts.job, &mut settlement, executor_at_fault, clock.slot)
+    }
+
+    /// 분쟁 게임 개시 - 챌린저가 per-step 레지스터 상태 트레이스 루트와 첫/마지막 리프를 커밋
+    /// 첫 리프는 Job에서 유도한 초기 상태와 같고, 마지막 리프는 실행자의 최종 상태와 달라야 함
+    pub fn open_dispute(
+        ctx: Context<OpenDispute>,
+        trace_root: [u8; 32],
+        step_count: u32,
+        initial_state: TraceLeaf,
+        final_state: TraceLeaf,
+    ) -> Result<()> {
+        let job = &ctx.accounts.job;
+        let challenger = ctx.accounts.challenger.key();
+        let clock = Clock::get()?;
+
+        require!(job.status == JobStatus::Challenged, ErrorCode::InvalidJobStatus);
+        require!(job.challenger == Some(challenger), ErrorCode::NotChallenger);
+        require!(step_count > 0, ErrorCode::InvalidTraceLength);
+        require!(initial_state.verify(&trace_root, 0, step_count), ErrorCode::InvalidTraceProof);
+        require!(final_state.verify(&trace_root, step_count, step_count), ErrorCode::InvalidTraceProof);
+        let committed = job.trace_commitment.ok_or(ErrorCode::MissingTraceCommitment)?;
+        require!(
+            initial_state.state_hash == committed.initial_state_hash,
+            ErrorCode::TraceEndpointMismatch
+        );
+        require!(
+            final_state.state_hash != committed.final_state_hash,
+            ErrorCode::TraceAgreesWithResult
+        );
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.job = job.key();
+        dispute.challenger = challenger;
+        dispute.defender = job.executor.ok_or(ErrorCode::MissingJobExecutor)?;
+        dispute.challenger_root = trace_root;
+        dispute.defender_root = None;
+        dispute.step_count = step_count;
+        dispute.lo = 0;
+        dispute.hi = step_count;
+        dispute.lo_hash = initial_state.state_hash;
+        dispute.hi_challenger_hash = final_state.state_hash;
+        dispute.hi_defender_hash = [0u8; 32];
+        dispute.pending_mid_hash = None;
+        dispute.phase = DisputePhase::AwaitingResponse;
+        dispute.last_move_slot = clock.slot;
+        dispute.bump = ctx.bumps.dispute;
+
+        emit!(DisputeOpened {
+            job: dispute.job,
+            dispute: dispute.key(),
+            challenger,
+            defender: dispute.defender,
+            trace_root,
+            step_count,
+        });
+        Ok(())
+    }
+
+    /// 실행자 응답 - 자신의 트레이스 루트 커밋
+    /// 첫/마지막 리프는 Job의 trace_commitment와 같아야 함
+    /// (챌린저와 초기 상태는 같고 최종 상태는 다르므로 바로 이분 탐색 시작)
+    pub fn respond_dispute(
+        ctx: Context<RespondDispute>,
+        trace_root: [u8; 32],
+        initial_state: TraceLeaf,
+        final_state: TraceLeaf,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let committed = ctx.accounts.job.trace_commitment.ok_or(ErrorCode::MissingTraceCommitment)?;
+        let dispute = &mut ctx.accounts.dispute;
+
+        require!(dispute.phase == DisputePhase::AwaitingResponse, ErrorCode::InvalidDisputePhase);
+        require!(initial_state.verify(&trace_root, 0, dispute.step_count), ErrorCode::InvalidTraceProof);
+        require!(
+            final_state.verify(&trace_root, dispute.step_count, dispute.step_count),
+            ErrorCode::InvalidTraceProof
+        );
+        require!(
+            initial_state.state_hash == committed.initial_state_hash
+                && final_state.state_hash == committed.final_state_hash,
+            ErrorCode::TraceEndpointMismatch
+        );
+
+        dispute.defender_root = Some(trace_root);
+        dispute.hi_defender_hash = final_state.state_hash;
+        dispute.phase = next_bisection_phase(dispute);
+        dispute.last_move_slot = clock.slot;
+        Ok(())
+    }
+
+    /// 이분 탐색 한 수 - 실행자가 먼저 mid 리프를 공개, 챌린저가 자신의 mid 리프로 응수
+    /// 일치하면 [mid, hi], 불일치하면 [lo, mid]로 구간 축소
+    pub fn bisect_dispute(ctx: Context<BisectDispute>, mid_state: TraceLeaf) -> Result<()> {
+        let clock = Clock::get()?;
+        let mover = ctx.accounts.mover.key();
+        let dispute = &mut ctx.accounts.dispute;
+        let mid = dispute.lo + (dispute.hi - dispute.lo) / 2;
+
+        match dispute.phase {
+            DisputePhase::DefenderTurn => {
+                require_keys_eq!(mover, dispute.defender, ErrorCode::NotYourTurn);
+                let root = dispute.defender_root.ok_or(ErrorCode::InvalidDisputePhase)?;
+                require!(mid_state.verify(&root, mid, dispute.step_count), ErrorCode::InvalidTraceProof);
+                dispute.pending_mid_hash = Some(mid_state.state_hash);
+                dispute.phase = DisputePhase::ChallengerTurn;
+            }
+            DisputePhase::ChallengerTurn => {
+                require_keys_eq!(mover, dispute.challenger, ErrorCode::NotYourTurn);
+                require!(
+                    mid_state.verify(&dispute.challenger_root, mid, dispute.step_count),
+                    ErrorCode::InvalidTraceProof
+                );
+                let defender_mid = dispute.pending_mid_hash.take().ok_or(ErrorCode::InvalidDisputePhase)?;
+                if defender_mid == mid_state.state_hash {
+                    dispute.lo = mid;
+                    dispute.lo_hash = defender_mid;
+                } else {
+                    dispute.hi = mid;
+                    dispute.hi_challenger_hash = mid_state.state_hash;
+                    dispute.hi_defender_hash = defender_mid;
+                }
+                dispute.phase = next_bisection_phase(dispute);
+                emit!(DisputeBisected {
+                    job: dispute.job,
+                    dispute: dispute.key(),
+                    lo: dispute.lo,
+                    hi: dispute.hi,
+                });
+            }
+            _ => return err!(ErrorCode::InvalidDisputePhase),
+        }
+
+        dispute.last_move_slot = clock.slot;
+        Ok(())
+    }
+
+    /// 단일 op 재실행으로 판정 - 누구나 호출 가능
+    /// 합의된 lo 상태에서 lo번째 op를 실행한 결과가 실행자의 hi 리프와 같으면 실행자 승
+    pub fn resolve_dispute_step(
+        ctx: Context<ResolveDispute>,
+        pre_registers: Registers,
+        ir_bytes: Vec<u8>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let dispute = &ctx.accounts.dispute;
+
+        require!(dispute.phase == DisputePhase::OneStep, ErrorCode::InvalidDisputePhase);
+        let digest = anchor_lang::solana_program::hash::hash(&ir_bytes).to_bytes();
+        require!(digest == ctx.accounts.job.expected_code_digest, ErrorCode::CodeDigestMismatch);
+        require!(
+            fraud_proof::state_hash(dispute.lo, &pre_registers) == dispute.lo_hash,
+            ErrorCode::PreStateMismatch
+        );
+
+        // 챌린저가 잘못된 step_count로 게임을 열었다면 챌린저 패
+        let executor_at_fault = if fraud_proof::count_ops(&ir_bytes)? != dispute.step_count {
+            false
+        } else {
+            let op = fraud_proof::op_at(&ir_bytes, dispute.lo)?;
+            let post = fraud_proof::execute(&pre_registers, op);
+            fraud_proof::state_hash(dispute.hi, &post) != dispute.hi_defender_hash
+        };
+
+        let mut settlement = Settlement {
+            config: &ctx.accounts.config,
+            executor_record: &mut ctx.accounts.executor_record,
+            challenger: ctx.accounts.challenger.to_account_info(),
+            treasury: ctx.accounts.treasury.to_account_info(),
+        };
+        finish_dispute(
+            &mut ctx.accounts.dispute,
+            &mut ctx.accounts.job,
+            &mut settlement,
+            executor_at_fault,
+            clock.slot,
+        )
+    }
+
+    /// 차례인 쪽이 DISPUTE_MOVE_TIMEOUT_SLOTS 동안 응답하지 않으면 상대방 승
+    pub fn timeout_dispute(ctx: Context<ResolveDispute>) -> Result<()> {
+        let clock = Clock::get()?;
+        let dispute = &ctx.accounts.dispute;
+
+        require!(
+            clock.slot >= dispute.last_move_slot + DISPUTE_MOVE_TIMEOUT_SLOTS,
+            ErrorCode::DisputeTimeoutNotReached
+        );
+        let executor_at_fault = match dispute.phase {
+            DisputePhase::AwaitingResponse | DisputePhase::DefenderTurn => true,
+            DisputePhase::ChallengerTurn => false,
+            _ => return err!(ErrorCode::InvalidDisputePhase),
+        };
+
+        let mut settlement = Settlement {
+            config: &ctx.accounts.config,
+            executor_record: &mut ctx.accounts.executor_record,
+            challenger: ctx.accounts.challenger.to_account_info(),
+            treasury: ctx.accounts.treasury.to_account_info(),
+        };
+        finish_dispute(
+            &mut ctx.accounts.dispute,
+            &mut ctx.accounts.job,
+            &mut settlement,
+            executor_at_fault,
+            clock.slot,
+        )
+    }
+
+    /// 지표 히스토리 링 버퍼 생성 - authority 전용, 1회
+    pub fn init_metrics_history(ctx: Context<InitMetricsHistory>) -> Result<()> {
+        let history = &mut ctx.accounts.metrics_history;
+        history.head = 0;
+        history.windows = Vec::with_capacity(METRICS_HISTORY_LEN);
+        history.bump = ctx.bumps.metrics_history;
+        Ok(())
+    }
+
+    /// 30초 지표 발행 - executor 전용, MetricsHistory 링 버퍼에 기록 (window_start_slot 단조 증가)
     pub fn publish_metrics(
-        _ctx: Context<PublishMetrics>,
+        ctx: Context<PublishMetrics>,
         window_start_slot: u64,
         tvl: u64,
         utilization_bp: u16,
         total_users: u32,
         proof_ptr_hash: Option<[u8; 32]>,
     ) -> Result<()> {
+        require!(utilization_bp <= 10_000, ErrorCode::InvalidBasisPoints);
+        let clock = Clock::get()?;
+        let pushed = ctx.accounts.metrics_history.push(MetricsWindow {
+            window_start_slot,
+            published_slot: clock.slot,
+            executor: ctx.accounts.executor.key(),
+            tvl,
+            utilization_bp,
+            total_users,
+            proof_ptr_hash,
+        });
+        require!(pushed, ErrorCode::MetricsWindowNotMonotonic);
+
         emit!(MetricsPublished { 
             window_start_slot, 
             tvl, 
@@ -147,17 +1055,192 @@ pub mod conf_coprocessor {
         Ok(())
     }
 
-    /// Record oracle price snapshot at specific slot
-    pub fn record_oracle_snapshot(
-        ctx: Context<RecordOracleSnapshot>,
+    /// 윈도우 TVL 기여 커밋 - 게시한 실행자가 proof_ptr_hash(= 기여 머클 루트)를 온체인에 고정
+    /// committed_slot + challenge_window_slots 동안 챌린지 가능, post_result와 같은 본드 조건/잠금 적용
+    pub fn commit_metrics(
+        ctx: Context<CommitMetrics>,
+        window_start_slot: u64,
+        contributions_root: [u8; 32],
+        contribution_count: u32,
+    ) -> Result<()> {
+        let window = *ctx
+            .accounts
+            .metrics_history
+            .iter_recent()
+            .find(|w| w.window_start_slot == window_start_slot)
+            .ok_or(ErrorCode::MetricsWindowNotFound)?;
+        require_keys_eq!(window.executor, ctx.accounts.executor.key(), ErrorCode::NotMetricsPublisher);
+        require!(window.proof_ptr_hash == Some(contributions_root), ErrorCode::MetricsRootMismatch);
+        require!(contribution_count > 0, ErrorCode::InvalidContributionProof);
+        require!(
+            ctx.accounts.executor_record.bond_lamports >= ctx.accounts.config.min_bond_lamports,
+            ErrorCode::InsufficientBond
+        );
+        let clock = Clock::get()?;
+        let record = &mut ctx.accounts.executor_record;
+        record.bond_locked_until_slot = record
+            .bond_locked_until_slot
+            .max(clock.slot + ctx.accounts.config.challenge_window_slots);
+
+        let commitment = &mut ctx.accounts.metrics_commitment;
+        commitment.executor = window.executor;
+        commitment.window_start_slot = window_start_slot;
+        commitment.tvl = window.tvl;
+        commitment.contributions_root = contributions_root;
+        commitment.contribution_count = contribution_count;
+        commitment.committed_slot = clock.slot;
+        commitment.status = MetricsStatus::Committed;
+        commitment.challenger = None;
+        commitment.challenge_evidence_hash = None;
+        commitment.bump = ctx.bumps.metrics_commitment;
+        commitment.challenger_stake_lamports = 0;
+
+        emit!(MetricsCommitted {
+            commitment: commitment.key(),
+            executor: window.executor,
+            window_start_slot,
+            tvl: window.tvl,
+            contributions_root,
+            contribution_count,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// 지표 커밋 챌린지 - 챌린지 윈도우 안에서 누구나
+    /// 챌린저는 config.challenger_stake_lamports를 커밋먼트 계정에 예치 (기각 시 몰수)
+    pub fn challenge_metrics(ctx: Context<ChallengeMetrics>, evidence_hash: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let stake = ctx.accounts.config.challenger_stake_lamports;
+
+        {
+            let commitment = &ctx.accounts.metrics_commitment;
+            require!(commitment.status == MetricsStatus::Committed, ErrorCode::InvalidMetricsStatus);
+            require!(
+                clock.slot < commitment.committed_slot + ctx.accounts.config.challenge_window_slots,
+                ErrorCode::ChallengeWindowClosed
+            );
+        }
+
+        if stake > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.challenger.to_account_info(),
+                        to: ctx.accounts.metrics_commitment.to_account_info(),
+                    },
+                ),
+                stake,
+            )?;
+        }
+
+        let commitment = &mut ctx.accounts.metrics_commitment;
+        commitment.status = MetricsStatus::Challenged;
+        commitment.challenger = Some(ctx.accounts.challenger.key());
+        commitment.challenge_evidence_hash = Some(evidence_hash);
+        commitment.challenger_stake_lamports = stake;
+        ctx.accounts.executor_record.open_challenges += 1;
+
+        emit!(MetricsChallenged {
+            commitment: commitment.key(),
+            challenger: ctx.accounts.challenger.key(),
+            evidence_hash,
+            slot: clock.slot,
+            stake_lamports: stake,
+        });
+        Ok(())
+    }
+
+    /// 객관적 판정 - 루트에 포함된 기여 Job이 커밋 시점에 확정 가능하지 않았음을 증명하면 즉시 슬래싱
+    /// 기여 계정이 살아 있는 Job이어야 함 (닫힌 Job/Job 아닌 키는 resolve_metrics_challenge로)
+    pub fn prove_invalid_contribution(
+        ctx: Context<ProveInvalidContribution>,
+        leaf_index: u32,
+        balance_handle: [u8; 32],
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let commitment = &ctx.accounts.metrics_commitment;
+        require!(commitment.status == MetricsStatus::Challenged, ErrorCode::InvalidMetricsStatus);
+
+        let job_info = ctx.accounts.contribution_job.to_account_info();
+        let leaf = metrics::contribution_leaf(job_info.key, &balance_handle);
+        require!(
+            merkle::verify_proof(
+                leaf,
+                leaf_index,
+                commitment.contribution_count,
+                &proof,
+                &commitment.contributions_root
+            ),
+            ErrorCode::InvalidContributionProof
+        );
+        let job = metrics::load_job(&job_info).ok_or(ErrorCode::ContributionUnprovable)?;
+        require!(
+            !metrics::was_finalized_at(&job, commitment.committed_slot),
+            ErrorCode::ContributionValid
+        );
+
+        let clock = Clock::get()?;
+        let mut settlement = Settlement {
+            config: &ctx.accounts.config,
+            executor_record: &mut ctx.accounts.executor_record,
+            challenger: ctx.accounts.challenger.to_account_info(),
+            treasury: ctx.accounts.treasury.to_account_info(),
+        };
+        settle_metrics_challenge(&mut ctx.accounts.metrics_commitment, &mut settlement, true, clock.slot)
+    }
+
+    /// authority 판정 (합계 불일치 등 주관적 분쟁) - 과실이면 슬래싱 후 Invalidated, 아니면 Committed 복귀
+    pub fn resolve_metrics_challenge(ctx: Context<ResolveMetricsChallenge>, executor_at_fault: bool) -> Result<()> {
+        require!(
+            ctx.accounts.metrics_commitment.status == MetricsStatus::Challenged,
+            ErrorCode::InvalidMetricsStatus
+        );
+        let clock = Clock::get()?;
+        let mut settlement = Settlement {
+            config: &ctx.accounts.config,
+            executor_record: &mut ctx.accounts.executor_record,
+            challenger: ctx.accounts.challenger.to_account_info(),
+            treasury: ctx.accounts.treasury.to_account_info(),
+        };
+        settle_metrics_challenge(&mut ctx.accounts.metrics_commitment, &mut settlement, executor_at_fault, clock.slot)
+    }
+
+    /// 지표 커밋 정리 - 챌린지 윈도우 경과 후 분쟁 중이 아니면 게시 실행자가 렌트 회수
+    pub fn close_metrics_commitment(ctx: Context<CloseMetricsCommitment>) -> Result<()> {
+        let commitment = &ctx.accounts.metrics_commitment;
+        let clock = Clock::get()?;
+
+        require!(commitment.status != MetricsStatus::Challenged, ErrorCode::InvalidMetricsStatus);
+        require!(
+            clock.slot >= commitment.committed_slot + ctx.accounts.config.challenge_window_slots,
+            ErrorCode::ChallengeWindowNotPassed
+        );
+
+        emit!(MetricsCommitmentClosed {
+            commitment: commitment.key(),
+            executor: commitment.executor,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// Record oracle price snapshot at specific slot - 가격/신뢰구간/슬롯은 피드 계정에서 직접 읽음
+    pub fn record_oracle_snapshot(
+        ctx: Context<RecordOracleSnapshot>,
         oracle_program: Pubkey,
         feed: Pubkey,
-        price_e9: i64,
-        conf_e9: u64,
         observed_slot: u64,
     ) -> Result<()> {
         // Validate oracle program key matches
         require_keys_eq!(ctx.accounts.oracle_program_acc.key(), oracle_program, ErrorCode::BadOracleProgram);
+        require_keys_eq!(ctx.accounts.feed_acc.key(), feed, ErrorCode::OracleFeedMismatch);
+
+        let feed_acc = &ctx.accounts.feed_acc;
+        let reading = oracle::read_feed(&oracle_program, feed_acc.owner, &feed_acc.try_borrow_data()?)?;
+        require!(reading.observed_slot == observed_slot, ErrorCode::SnapshotSlotMismatch);
+        let oracle::FeedReading { price_e9, conf_e9, .. } = reading;
 
         // Initialize the snapshot account
         let snapshot = &mut ctx.accounts.snapshot;
@@ -170,6 +1253,7 @@ pub mod conf_coprocessor {
         snapshot.observed_slot = observed_slot;
         snapshot.ptr_hash = ptr_hash;
         snapshot.bump = ctx.bumps.snapshot;
+        snapshot.recorder = ctx.accounts.recorder.key();
 
         emit!(OracleSnapshotRecorded {
             snapshot: ctx.accounts.snapshot.key(),
@@ -183,31 +1267,571 @@ pub mod conf_coprocessor {
         Ok(())
     }
 
+    /// 보존 기간이 지난 스냅샷 닫기 - 기록한 실행자에게 rent 환불
+    pub fn close_snapshot(ctx: Context<CloseSnapshot>) -> Result<()> {
+        let snapshot = &ctx.accounts.snapshot;
+        let clock = Clock::get()?;
+
+        require!(
+            clock.slot >= snapshot.observed_slot.saturating_add(ctx.accounts.config.snapshot_retention_slots),
+            ErrorCode::SnapshotRetentionActive
+        );
+
+        emit!(OracleSnapshotClosed {
+            snapshot: snapshot.key(),
+            recorder: snapshot.recorder,
+            observed_slot: snapshot.observed_slot,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// 여러 피드를 한 번에 기록 - remaining_accounts = 피드 계정들, oracle_programs = 피드별 선언한 오라클 프로그램
+    /// ptr_hash = merkle_root(각 피드의 oracle_ptr_hash), 관측 슬롯 폭은 config.max_bundle_slot_spread 이내
+    pub fn record_oracle_bundle(
+        ctx: Context<RecordOracleBundle>,
+        bundle_id: u64,
+        oracle_programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        let feeds = ctx.remaining_accounts;
+        require!(
+            !feeds.is_empty() && feeds.len() <= MAX_BUNDLE_FEEDS && oracle_programs.len() == feeds.len(),
+            ErrorCode::InvalidBundleSize
+        );
+
+        let mut entries: Vec<BundleEntry> = Vec::with_capacity(feeds.len());
+        for (info, oracle_program) in feeds.iter().zip(&oracle_programs) {
+            require!(
+                !entries.iter().any(|e| e.feed == info.key()),
+                ErrorCode::DuplicateBundleFeed
+            );
+            let reading = oracle::read_feed(oracle_program, info.owner, &info.try_borrow_data()?)?;
+            entries.push(BundleEntry {
+                oracle_program: *oracle_program,
+                feed: info.key(),
+                price_e9: reading.price_e9,
+                conf_e9: reading.conf_e9,
+                observed_slot: reading.observed_slot,
+            });
+        }
+
+        let min_slot = entries.iter().map(|e| e.observed_slot).min().unwrap_or_default();
+        let max_slot = entries.iter().map(|e| e.observed_slot).max().unwrap_or_default();
+        require!(
+            max_slot - min_slot <= ctx.accounts.config.max_bundle_slot_spread,
+            ErrorCode::BundleSlotSpreadTooWide
+        );
+
+        let leaves: Vec<[u8; 32]> = entries.iter().map(BundleEntry::ptr_hash).collect();
+        let ptr_hash = merkle::merkle_root(&leaves);
+
+        let bundle = &mut ctx.accounts.bundle;
+        bundle.recorder = ctx.accounts.recorder.key();
+        bundle.bundle_id = bundle_id;
+        bundle.min_slot = min_slot;
+        bundle.max_slot = max_slot;
+        bundle.ptr_hash = ptr_hash;
+        bundle.bump = ctx.bumps.bundle;
+
+        emit!(OracleBundleRecorded {
+            bundle: bundle.key(),
+            recorder: bundle.recorder,
+            bundle_id,
+            feeds: entries.iter().map(|e| e.feed).collect(),
+            min_slot,
+            max_slot,
+            ptr_hash,
+        });
+        bundle.entries = entries;
+        Ok(())
+    }
+
+    /// 보존 기간이 지난 번들 닫기 - 기준은 가장 늦은 관측 슬롯
+    pub fn close_oracle_bundle(ctx: Context<CloseOracleBundle>) -> Result<()> {
+        let bundle = &ctx.accounts.bundle;
+        let clock = Clock::get()?;
+
+        require!(
+            clock.slot >= bundle.max_slot.saturating_add(ctx.accounts.config.snapshot_retention_slots),
+            ErrorCode::SnapshotRetentionActive
+        );
+
+        emit!(OracleBundleClosed {
+            bundle: bundle.key(),
+            recorder: bundle.recorder,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
+    /// 같은 피드의 기존 스냅샷들로 TWAP/중앙값 집계 - remaining_accounts = OracleSnapshot (관측 슬롯 오름차순)
+    /// 모든 샘플은 [from_slot, to_slot] 안에 있어야 하고(to_slot은 현재 슬롯 이하) 결과는 oracle-agg-v1 해시로 참조
+    pub fn aggregate_oracle_snapshots(
+        ctx: Context<AggregateOracleSnapshots>,
+        oracle_program: Pubkey,
+        feed: Pubkey,
+        from_slot: u64,
+        to_slot: u64,
+    ) -> Result<()> {
+        let infos = ctx.remaining_accounts;
+        require!(
+            !infos.is_empty() && infos.len() <= MAX_AGGREGATE_SAMPLES,
+            ErrorCode::InvalidSampleCount
+        );
+        require!(to_slot <= Clock::get()?.slot, ErrorCode::AggregateRangeInFuture);
+
+        let mut samples = Vec::with_capacity(infos.len());
+        for info in infos {
+            require!(info.owner == &crate::ID, ErrorCode::AggregateFeedMismatch);
+            let snap = OracleSnapshot::try_deserialize(&mut &info.try_borrow_data()?[..])
+                .map_err(|_| error!(ErrorCode::AggregateFeedMismatch))?;
+            require!(
+                snap.oracle_program == oracle_program && snap.feed == feed,
+                ErrorCode::AggregateFeedMismatch
+            );
+            samples.push(oracle::Sample {
+                slot: snap.observed_slot,
+                price_e9: snap.price_e9,
+                conf_e9: snap.conf_e9,
+            });
+        }
+        let agg = oracle::aggregate(&samples, from_slot, to_slot)?;
+        let sample_count = samples.len() as u16;
+
+        let aggregated = &mut ctx.accounts.aggregated;
+        aggregated.oracle_program = oracle_program;
+        aggregated.feed = feed;
+        aggregated.from_slot = from_slot;
+        aggregated.to_slot = to_slot;
+        aggregated.twap_e9 = agg.twap_e9;
+        aggregated.median_e9 = agg.median_e9;
+        aggregated.min_e9 = agg.min_e9;
+        aggregated.max_e9 = agg.max_e9;
+        aggregated.conf_e9 = agg.conf_e9;
+        aggregated.sample_count = sample_count;
+        aggregated.ptr_hash = aggregated.compute_ptr_hash();
+        aggregated.bump = ctx.bumps.aggregated;
+        aggregated.recorder = ctx.accounts.recorder.key();
+        aggregated.observed_slot = agg.observed_slot;
+
+        emit!(OracleAggregateRecorded {
+            aggregated: aggregated.key(),
+            oracle_program,
+            feed,
+            from_slot,
+            to_slot,
+            twap_e9: agg.twap_e9,
+            median_e9: agg.median_e9,
+            sample_count,
+            ptr_hash: aggregated.ptr_hash,
+        });
+        Ok(())
+    }
+
+    /// 보존 기간이 지난 집계 스냅샷 닫기
+    pub fn close_aggregated_snapshot(ctx: Context<CloseAggregatedSnapshot>) -> Result<()> {
+        let aggregated = &ctx.accounts.aggregated;
+        let clock = Clock::get()?;
+
+        require!(
+            clock.slot >= aggregated.to_slot.saturating_add(ctx.accounts.config.snapshot_retention_slots),
+            ErrorCode::SnapshotRetentionActive
+        );
+
+        emit!(OracleAggregateClosed {
+            aggregated: aggregated.key(),
+            recorder: aggregated.recorder,
+            slot: clock.slot,
+        });
+        Ok(())
+    }
+
 }
 
 /* ========== Helper Functions ========== */
-fn oracle_ptr_hash(
-    oracle_program: &Pubkey,
-    feed: &Pubkey,
-    price_e9: i64,
-    conf_e9: u64,
-    observed_slot: u64,
-) -> [u8; 32] {
+
+/// 결과 커밋 = keccak("job-result-v1" || job || result(le) || salt)
+/// executor는 post_result 시 트레이스 최종 상태의 출력 레지스터로 result_commitment를 계산해야 reveal 가능
+pub fn result_commitment_hash(job: &Pubkey, result: u64, salt: &[u8; 32]) -> [u8; 32] {
     hashv(&[
-        b"oracle-snap-v1",
-        oracle_program.as_ref(),
-        feed.as_ref(),
-        &price_e9.to_le_bytes(),
-        &conf_e9.to_le_bytes(),
-        &observed_slot.to_le_bytes(),
+        b"job-result-v1",
+        job.as_ref(),
+        &result.to_le_bytes(),
+        salt,
     ]).to_bytes()
 }
 
+/// Job 제출 인자 (inline / buffer / digest 제출 경로 공용)
+struct JobSpec {
+    commitment: [u8; 32],
+    da_ptr_hash: Option<[u8; 32]>,
+    reveal_after_slot: u64,
+    function_id: u16,
+    context_data: [u8; 32],
+    code_digest: [u8; 32],
+    ir_account: Option<Pubkey>,
+    oracle_guard: Option<OracleGuard>,
+    callback: Option<CallbackSpec>,
+    caller_program: Option<Pubkey>,
+    parent_jobs: Vec<Pubkey>,
+}
+
+/// 새 Job 초기화 + JobSubmitted 이벤트 (제출 경로 공용)
+/// FunctionRecord의 허용 다이제스트/챌린지 윈도우/필수 입력을 적용
+/// remaining_accounts = [pins..., 부모 Job...]
+/// - pins는 required_inputs 순서대로 필수 - 모든 입력 계정을 제출 시 고정
+/// - parent_jobs는 고정된 JobResult 입력으로 뒤에 추가 (post_result에서 부모 확정/결과 커밋 검증)
+/// - JobResult로 고정된 Job은 쓰기 가능으로 전달, dependent_jobs 증가 (같은 submitter/caller_program의 Job만)
+fn init_job(
+    job: &mut Account<Job>,
+    config: &Config,
+    function: &FunctionRecord,
+    submitter: Pubkey,
+    bump: u8,
+    spec: JobSpec,
+    remaining: &[AccountInfo],
+) -> Result<()> {
+    require_active(config, spec.function_id)?;
+    require_allowed_digest(function, &spec.code_digest)?;
+    require!(remaining.len() >= spec.parent_jobs.len(), ErrorCode::MissingDependencyAccount);
+    let (pins, parents) = remaining.split_at(remaining.len() - spec.parent_jobs.len());
+    for (info, parent) in parents.iter().zip(&spec.parent_jobs) {
+        require_keys_eq!(info.key(), *parent, ErrorCode::MissingDependencyAccount);
+    }
+    require!(pins.len() == function.required_inputs.len(), ErrorCode::MissingExternalInput);
+    require!(
+        function.required_inputs.len() + spec.parent_jobs.len() <= MAX_REQUIRED_INPUTS,
+        ErrorCode::TooManyRequiredInputs
+    );
+    require!(!spec.parent_jobs.contains(&job.key()), ErrorCode::InvalidParentJob);
+    if let Some(guard) = &spec.oracle_guard {
+        require!(guard.conf_band_bp <= 10_000, ErrorCode::InvalidBasisPoints);
+        require!(
+            function
+                .required_inputs
+                .iter()
+                .any(|k| matches!(k, InputKind::OracleSnapshot | InputKind::OracleBundle)),
+            ErrorCode::OracleGuardWithoutSnapshot
+        );
+    }
+    if let Some(cb) = &spec.callback {
+        // 콜백은 함수를 등록한 소비 프로그램만 (자기 자신 재진입 금지)
+        require_keys_eq!(cb.program_id, function.consumer_program, ErrorCode::CallbackProgramMismatch);
+        require_keys_neq!(cb.program_id, crate::ID, ErrorCode::CallbackProgramMismatch);
+    }
+    let clock = Clock::get()?;
+
+    job.commitment = spec.commitment;
+    job.da_ptr_hash = spec.da_ptr_hash;
+    job.expected_code_digest = spec.code_digest;
+    job.result_commitment = None;
+    job.external_ptr_hash = None;
+    job.status = JobStatus::Submitted;
+    job.posted_slot = None;
+    job.reveal_after_slot = spec.reveal_after_slot;
+    job.function_id = spec.function_id;
+    job.context_data = spec.context_data;
+    job.submitter = submitter;
+    job.bump = bump;
+    job.challenger = None;
+    job.challenge_evidence_hash = None;
+    job.revealed_result = None;
+    job.executor = None;
+    job.submitted_slot = clock.slot;
+    job.consumed_slot = None;
+    job.consumer_program = function.consumer_program;
+    job.challenge_window_slots = function.challenge_window_slots.unwrap_or(config.challenge_window_slots);
+    job.inputs = function
+        .required_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| JobInput { kind: *kind, pinned: Some(pins[i].key()) })
+        .chain(
+            spec.parent_jobs
+                .iter()
+                .map(|parent| JobInput { kind: InputKind::JobResult, pinned: Some(*parent) }),
+        )
+        .collect();
+    job.oracle_guard = spec.oracle_guard;
+    job.callback = spec.callback;
+    job.caller_program = spec.caller_program;
+    job.challenger_stake_lamports = 0;
+    job.trace_commitment = None;
+    job.dependent_jobs = 0;
+    job.callback_invoked_slot = None;
+    require_parent_consent(&job.inputs, remaining, &submitter, spec.caller_program)?;
+    update_dependencies(&job.inputs, remaining, true)?;
+
+    emit!(JobSubmitted {
+        job: job.key(),
+        submitter,
+        commitment: spec.commitment,
+        da_ptr_hash: spec.da_ptr_hash,
+        expected_code_digest: spec.code_digest,
+        function_id: spec.function_id,
+        context_data: spec.context_data,
+        consumer_program: function.consumer_program,
+        ir_account: spec.ir_account,
+        oracle_guard: spec.oracle_guard,
+        callback: spec.callback,
+        caller_program: spec.caller_program,
+        parent_jobs: spec.parent_jobs,
+        slot: clock.slot,
+    });
+    Ok(())
+}
+
+/// 제출 경로의 호출 프로그램 - 소비 프로그램의 ["coproc-caller"] PDA가 서명했으면 그 프로그램
+/// PDA 서명은 해당 프로그램만 만들 수 있으므로 중첩 CPI에서도 서명한 프로그램이 기록됨
+/// CPI 제출은 서명 필수, 최상위 직접 제출이면 서명 없이 None
+fn caller_program(function: &FunctionRecord, caller_authority: Option<&Signer>) -> Result<Option<Pubkey>> {
+    let Some(authority) = caller_authority else {
+        require!(get_stack_height() <= TRANSACTION_LEVEL_STACK_HEIGHT, ErrorCode::CallerAuthorityMissing);
+        return Ok(None);
+    };
+    require_keys_eq!(
+        authority.key(),
+        Pubkey::find_program_address(&[CALLER_SEED], &function.consumer_program).0,
+        ErrorCode::NotCallerAuthority
+    );
+    Ok(Some(function.consumer_program))
+}
+
+/// submit/post 허용 여부 (전역 + function_id 단위 정지)
+fn require_active(config: &Config, function_id: u16) -> Result<()> {
+    require!(!config.paused, ErrorCode::ProgramPaused);
+    require!(!config.paused_functions.contains(&function_id), ErrorCode::FunctionPaused);
+    Ok(())
+}
+
+/// FunctionRecord 스펙 교체 - 목록 길이 상한 검사 후 기록
+fn set_function_spec(
+    record: &mut FunctionRecord,
+    allowed_digests: Vec<[u8; 32]>,
+    required_inputs: Vec<InputKind>,
+    challenge_window_slots: Option<u64>,
+) -> Result<()> {
+    require!(allowed_digests.len() <= MAX_ALLOWED_DIGESTS, ErrorCode::TooManyAllowedDigests);
+    require!(required_inputs.len() <= MAX_REQUIRED_INPUTS, ErrorCode::TooManyRequiredInputs);
+    record.allowed_digests = allowed_digests;
+    record.required_inputs = required_inputs;
+    record.challenge_window_slots = challenge_window_slots;
+    Ok(())
+}
+
+fn require_allowed_digest(record: &FunctionRecord, digest: &[u8; 32]) -> Result<()> {
+    require!(record.is_allowed(digest), ErrorCode::DigestNotAllowed);
+    Ok(())
+}
+
+/// 고정된 부모 Job은 닫기/취소가 막히므로 부모 쪽이 같은 주체여야 함
+/// (같은 submitter, 또는 같은 소비 프로그램이 ["coproc-caller"] 서명으로 제출한 Job)
+fn require_parent_consent(
+    inputs: &[JobInput],
+    accounts: &[AccountInfo],
+    submitter: &Pubkey,
+    caller_program: Option<Pubkey>,
+) -> Result<()> {
+    let pinned_jobs = inputs
+        .iter()
+        .filter(|input| input.kind == InputKind::JobResult)
+        .filter_map(|input| input.pinned);
+    for key in pinned_jobs {
+        let info = accounts
+            .iter()
+            .find(|info| info.key() == key)
+            .ok_or(ErrorCode::MissingDependencyAccount)?;
+        require!(info.owner == &crate::ID, ErrorCode::MissingDependencyAccount);
+        let parent = Job::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            parent.submitter == *submitter || (caller_program.is_some() && parent.caller_program == caller_program),
+            ErrorCode::ParentJobNotConsented
+        );
+    }
+    Ok(())
+}
+
+/// JobResult 입력으로 고정된 Job들의 dependent_jobs 증감 (제출 시 +1, close/cancel 시 -1)
+/// 고정된 Job 계정은 accounts에서 키로 찾으며 쓰기 가능해야 함
+fn update_dependencies(inputs: &[JobInput], accounts: &[AccountInfo], increment: bool) -> Result<()> {
+    let pinned_jobs = inputs
+        .iter()
+        .filter(|input| input.kind == InputKind::JobResult)
+        .filter_map(|input| input.pinned);
+    for key in pinned_jobs {
+        let info = accounts
+            .iter()
+            .find(|info| info.key() == key)
+            .ok_or(ErrorCode::MissingDependencyAccount)?;
+        require!(info.owner == &crate::ID && info.is_writable, ErrorCode::MissingDependencyAccount);
+        let mut dependency = Job::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        dependency.dependent_jobs = if increment {
+            dependency.dependent_jobs.checked_add(1).ok_or(ErrorCode::TooManyDependents)?
+        } else {
+            dependency.dependent_jobs.saturating_sub(1)
+        };
+        dependency.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+    Ok(())
+}
+
+fn emit_function_updated(record: &FunctionRecord) {
+    emit!(FunctionUpdated {
+        consumer_program: record.consumer_program,
+        function_id: record.function_id,
+        allowed_digests: record.allowed_digests.clone(),
+        required_inputs: record.required_inputs.clone(),
+        challenge_window_slots: record.challenge_window_slots,
+    });
+}
+
+/// 챌린지 판정에 필요한 계정 묶음 (판정 경로 공용)
+struct Settlement<'a, 'info> {
+    config: &'a Config,
+    executor_record: &'a mut Account<'info, ExecutorRecord>,
+    challenger: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+}
+
+/// 챌린지 판정 결과를 Job에 반영 (resolve_challenge / 분쟁 게임 공용)
+/// 챌린저 스테이크는 인용 시 반환, 기각 시 treasury로 몰수
+fn settle_challenge<'info>(
+    job: &mut Account<'info, Job>,
+    settlement: &mut Settlement<'_, 'info>,
+    executor_at_fault: bool,
+    slot: u64,
+) -> Result<()> {
+    let challenger = job.challenger.ok_or(ErrorCode::MissingChallenger)?;
+    let record = &mut settlement.executor_record;
+    record.open_challenges = record.open_challenges.saturating_sub(1);
+
+    let stake = job.challenger_stake_lamports;
+    let stake_recipient = if executor_at_fault { &settlement.challenger } else { &settlement.treasury };
+    **job.to_account_info().try_borrow_mut_lamports()? -= stake;
+    **stake_recipient.try_borrow_mut_lamports()? += stake;
+    job.challenger_stake_lamports = 0;
+
+    if executor_at_fault {
+        slash_executor(settlement, job.key())?;
+        job.result_commitment = None;
+        job.external_ptr_hash = None;
+        job.posted_slot = None;
+        job.executor = None;
+        job.trace_commitment = None;
+        job.status = JobStatus::Submitted;
+    } else {
+        job.status = JobStatus::Posted;
+    }
+    job.challenger = None;
+    job.challenge_evidence_hash = None;
+
+    emit!(ChallengeResolved {
+        job: job.key(),
+        challenger,
+        executor_at_fault,
+        slot,
+        stake_lamports: stake,
+    });
+    Ok(())
+}
+
+/// 지표 챌린지 판정 반영 (객관적 증명/authority 판정 공용)
+fn settle_metrics_challenge<'info>(
+    commitment: &mut Account<'info, MetricsCommitment>,
+    settlement: &mut Settlement<'_, 'info>,
+    executor_at_fault: bool,
+    slot: u64,
+) -> Result<()> {
+    let challenger = commitment.challenger.ok_or(ErrorCode::MissingChallenger)?;
+    let record = &mut settlement.executor_record;
+    record.open_challenges = record.open_challenges.saturating_sub(1);
+
+    let stake = commitment.challenger_stake_lamports;
+    let stake_recipient = if executor_at_fault { &settlement.challenger } else { &settlement.treasury };
+    **commitment.to_account_info().try_borrow_mut_lamports()? -= stake;
+    **stake_recipient.try_borrow_mut_lamports()? += stake;
+    commitment.challenger_stake_lamports = 0;
+
+    if executor_at_fault {
+        slash_executor(settlement, commitment.key())?;
+        commitment.status = MetricsStatus::Invalidated;
+    } else {
+        commitment.status = MetricsStatus::Committed;
+        commitment.challenger = None;
+        commitment.challenge_evidence_hash = None;
+    }
+
+    emit!(MetricsChallengeResolved {
+        commitment: commitment.key(),
+        challenger,
+        executor_at_fault,
+        slot,
+        stake_lamports: stake,
+    });
+    Ok(())
+}
+
+/// 본드 슬래싱 - min_bond_lamports(잔액 한도)만큼 차감
+/// challenger_reward_bp 비율은 챌린저에게, 나머지는 treasury로
+fn slash_executor(settlement: &mut Settlement, job: Pubkey) -> Result<()> {
+    let config = settlement.config;
+    let record = &mut settlement.executor_record;
+
+    let amount = record.bond_lamports.min(config.min_bond_lamports);
+    let challenger_reward = (amount as u128 * config.challenger_reward_bp as u128 / 10_000) as u64;
+    let treasury_amount = amount - challenger_reward;
+
+    record.bond_lamports -= amount;
+    **record.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **settlement.challenger.try_borrow_mut_lamports()? += challenger_reward;
+    **settlement.treasury.try_borrow_mut_lamports()? += treasury_amount;
+
+    emit!(ExecutorSlashed {
+        executor: record.executor,
+        job,
+        challenger: settlement.challenger.key(),
+        challenger_reward,
+        treasury_amount,
+        bond_lamports: record.bond_lamports,
+    });
+    Ok(())
+}
+
+/// 분쟁 종료 - Job에 판정 반영 후 분쟁 계정은 챌린저에게 닫음
+fn finish_dispute<'info>(
+    dispute: &mut Account<'info, Dispute>,
+    job: &mut Account<'info, Job>,
+    settlement: &mut Settlement<'_, 'info>,
+    executor_at_fault: bool,
+    slot: u64,
+) -> Result<()> {
+    require!(job.status == JobStatus::Challenged, ErrorCode::InvalidJobStatus);
+    settle_challenge(job, settlement, executor_at_fault, slot)?;
+
+    emit!(DisputeResolved {
+        job: job.key(),
+        dispute: dispute.key(),
+        executor_at_fault,
+        lo: dispute.lo,
+        hi: dispute.hi,
+        slot,
+    });
+    dispute.close(settlement.challenger.clone())
+}
+
+fn next_bisection_phase(dispute: &Dispute) -> DisputePhase {
+    if dispute.hi - dispute.lo <= 1 {
+        DisputePhase::OneStep
+    } else {
+        DisputePhase::DefenderTurn
+    }
+}
+
 /* ========== Accounts ========== */
 
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
-    #[account(init, payer = payer, space = 8 + Config::SIZE, seeds = [b"config"], bump)]
+    #[account(init, payer = payer, space = 8 + Config::SIZE, seeds = [CONFIG_SEED], bump)]
     pub config: Account<'info, Config>,
     pub authority: Signer<'info>,
     #[account(mut)]
@@ -216,72 +1840,700 @@ pub struct InitializeConfig<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(commitment: [u8;32])]
-pub struct SubmitJob<'info> {
-    #[account(seeds=[b"config"], bump=config.bump)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds=[CONFIG_SEED],
+        bump=config.bump,
+        constraint = config.pending_authority == Some(pending_authority.key()) @ ErrorCode::NotPendingAuthority
+    )]
+    pub config: Account<'info, Config>,
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCommittee<'info> {
+    #[account(mut, seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(consumer_program: Pubkey, function_id: u16)]
+pub struct RegisterFunction<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FunctionRecord::SIZE,
+        seeds = [FUNCTION_SEED, consumer_program.as_ref(), &function_id.to_le_bytes()],
+        bump
+    )]
+    pub function_record: Account<'info, FunctionRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFunction<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::NotFunctionOwner,
+        seeds = [FUNCTION_SEED, function_record.consumer_program.as_ref(), &function_record.function_id.to_le_bytes()],
+        bump = function_record.bump
+    )]
+    pub function_record: Account<'info, FunctionRecord>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(executor: Pubkey)]
+pub struct AddExecutor<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ExecutorRecord::SIZE,
+        seeds = [EXECUTOR_SEED, executor.as_ref()],
+        bump
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateExecutor<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor_record.executor.as_ref()],
+        bump = executor_record.bump
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveExecutor<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [EXECUTOR_SEED, executor_record.executor.as_ref()],
+        bump = executor_record.bump,
+        has_one = executor
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    /// CHECK: 본드 반환 대상 (executor_record.executor와 일치)
+    #[account(mut)]
+    pub executor: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositBond<'info> {
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor.key().as_ref()],
+        bump = executor_record.bump
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBond<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor.key().as_ref()],
+        bump = executor_record.bump
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8;32], da_ptr_hash: Option<[u8;32]>, reveal_after_slot: u64, function_id: u16)]
+pub struct SubmitJob<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = submitter,
+        space = 8 + Job::SIZE,
+        seeds = [JOB_SEED, commitment.as_ref(), submitter.key().as_ref()],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        seeds = [FUNCTION_SEED, function_record.consumer_program.as_ref(), &function_id.to_le_bytes()],
+        bump = function_record.bump
+    )]
+    pub function_record: Account<'info, FunctionRecord>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CPI 제출 시 함수 소비 프로그램의 ["coproc-caller"] PDA (invoke_signed 서명), 직접 제출이면 없음
+    pub caller_authority: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(buffer_id: u64, total_len: u32)]
+pub struct InitIrBuffer<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = IrBuffer::space(total_len),
+        seeds = [IR_BUFFER_SEED, owner.key().as_ref(), &buffer_id.to_le_bytes()],
+        bump
+    )]
+    pub ir_buffer: Account<'info, IrBuffer>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteIrBuffer<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [IR_BUFFER_SEED, owner.key().as_ref(), &ir_buffer.buffer_id.to_le_bytes()],
+        bump = ir_buffer.bump
+    )]
+    pub ir_buffer: Account<'info, IrBuffer>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8;32], da_ptr_hash: Option<[u8;32]>, reveal_after_slot: u64, function_id: u16)]
+pub struct SubmitJobByBuffer<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = submitter,
+        space = 8 + Job::SIZE,
+        seeds = [JOB_SEED, commitment.as_ref(), submitter.key().as_ref()],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        seeds = [FUNCTION_SEED, function_record.consumer_program.as_ref(), &function_id.to_le_bytes()],
+        bump = function_record.bump
+    )]
+    pub function_record: Account<'info, FunctionRecord>,
+    #[account(
+        seeds = [IR_BUFFER_SEED, ir_buffer.owner.as_ref(), &ir_buffer.buffer_id.to_le_bytes()],
+        bump = ir_buffer.bump,
+        constraint = ir_buffer.sealed @ ErrorCode::IrBufferNotSealed
+    )]
+    pub ir_buffer: Account<'info, IrBuffer>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CPI 제출 시 함수 소비 프로그램의 ["coproc-caller"] PDA (invoke_signed 서명), 직접 제출이면 없음
+    pub caller_authority: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseIrBuffer<'info> {
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [IR_BUFFER_SEED, owner.key().as_ref(), &ir_buffer.buffer_id.to_le_bytes()],
+        bump = ir_buffer.bump
+    )]
+    pub ir_buffer: Account<'info, IrBuffer>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(digest: [u8; 32], ir_bytes: Option<Vec<u8>>)]
+pub struct RegisterCode<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = CodeRecord::space(ir_bytes.as_ref().map_or(0, |ir| ir.len())),
+        seeds = [CODE_SEED, digest.as_ref()],
+        bump
+    )]
+    pub code_record: Account<'info, CodeRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8;32], da_ptr_hash: Option<[u8;32]>, reveal_after_slot: u64, function_id: u16)]
+pub struct SubmitJobByDigest<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = submitter,
+        space = 8 + Job::SIZE,
+        seeds = [JOB_SEED, commitment.as_ref(), submitter.key().as_ref()],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        seeds = [FUNCTION_SEED, function_record.consumer_program.as_ref(), &function_id.to_le_bytes()],
+        bump = function_record.bump
+    )]
+    pub function_record: Account<'info, FunctionRecord>,
+    #[account(seeds = [CODE_SEED, code_record.digest.as_ref()], bump = code_record.bump)]
+    pub code_record: Account<'info, CodeRecord>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CPI 제출 시 함수 소비 프로그램의 ["coproc-caller"] PDA (invoke_signed 서명), 직접 제출이면 없음
+    pub caller_authority: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct PostResult<'info> {
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor.key().as_ref()],
+        bump = executor_record.bump,
+        constraint = executor_record.status == ExecutorStatus::Active @ ErrorCode::ExecutorSuspended
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(
+        seeds = [FUNCTION_SEED, job.consumer_program.as_ref(), &job.function_id.to_le_bytes()],
+        bump = function_record.bump
+    )]
+    pub function_record: Account<'info, FunctionRecord>,
+    pub executor: Signer<'info>,
+    /// CHECK: instructions sysvar (위원회 서명 introspection)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Finalize<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct CancelJob<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = submitter,
+        has_one = submitter,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealResult<'info> {
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct InvokeCallback<'info> {
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+}
+
+#[derive(Accounts)]
+pub struct MarkConsumed<'info> {
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    /// consumer_program의 ["coproc-caller"] PDA, 또는 오프체인 소비자면 consumer_program 키 자체
+    #[account(
+        constraint = consumer.key() == job.consumer_program
+            || consumer.key() == Pubkey::find_program_address(&[CALLER_SEED], &job.consumer_program).0
+            @ ErrorCode::NotJobConsumer
+    )]
+    pub consumer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseJob<'info> {
+    #[account(
+        mut,
+        close = submitter,
+        has_one = submitter,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ChallengeResult<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor_record.executor.as_ref()],
+        bump = executor_record.bump,
+        constraint = job.executor == Some(executor_record.executor) @ ErrorCode::MissingJobExecutor
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveChallenge<'info> {
+    #[account(
+        seeds=[CONFIG_SEED],
+        bump=config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor_record.executor.as_ref()],
+        bump = executor_record.bump,
+        constraint = job.executor == Some(executor_record.executor) @ ErrorCode::MissingJobExecutor
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    /// CHECK: 슬래싱 보상/스테이크 반환 수령 계정 (job.challenger와 일치)
+    #[account(mut, constraint = job.challenger == Some(challenger.key()) @ ErrorCode::NotChallenger)]
+    pub challenger: UncheckedAccount<'info>,
+    /// CHECK: 슬래싱 잔여분 수령 계정 (config.treasury와 일치)
+    #[account(mut, address = config.treasury @ ErrorCode::BadTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: 이 Job의 분쟁 PDA - 분쟁이 없으면 빈 계정, 있으면 핸들러에서 역직렬화 후 close
+    #[account(mut, seeds = [DISPUTE_SEED, job.key().as_ref()], bump)]
+    pub dispute: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + Dispute::SIZE,
+        seeds = [DISPUTE_SEED, job.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RespondDispute<'info> {
+    #[account(
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, job.key().as_ref()],
+        bump = dispute.bump,
+        has_one = defender @ ErrorCode::NotYourTurn,
+        constraint = job.challenger == Some(dispute.challenger) @ ErrorCode::NotChallenger
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub defender: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BisectDispute<'info> {
+    #[account(
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, job.key().as_ref()],
+        bump = dispute.bump,
+        constraint = job.challenger == Some(dispute.challenger) @ ErrorCode::NotChallenger
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub mover: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds=[JOB_SEED, &job.commitment, job.submitter.as_ref()],
+        bump=job.bump
+    )]
+    pub job: Account<'info, Job>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor_record.executor.as_ref()],
+        bump = executor_record.bump,
+        constraint = job.executor == Some(executor_record.executor) @ ErrorCode::MissingJobExecutor
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, job.key().as_ref()],
+        bump = dispute.bump,
+        has_one = challenger,
+        constraint = job.challenger == Some(dispute.challenger) @ ErrorCode::NotChallenger
+    )]
+    pub dispute: Account<'info, Dispute>,
+    /// CHECK: 분쟁 계정 rent 반환 대상 (dispute.challenger와 일치)
+    #[account(mut)]
+    pub challenger: UncheckedAccount<'info>,
+    /// CHECK: 슬래싱 잔여분 수령 계정 (config.treasury와 일치)
+    #[account(mut, address = config.treasury @ ErrorCode::BadTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitMetricsHistory<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump, has_one = authority @ ErrorCode::UnauthorizedAuthority)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MetricsHistory::SIZE,
+        seeds = [METRICS_SEED],
+        bump
+    )]
+    pub metrics_history: Account<'info, MetricsHistory>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishMetrics<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [EXECUTOR_SEED, executor.key().as_ref()],
+        bump = executor_record.bump,
+        constraint = executor_record.status == ExecutorStatus::Active @ ErrorCode::ExecutorSuspended
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(mut, seeds=[METRICS_SEED], bump=metrics_history.bump)]
+    pub metrics_history: Account<'info, MetricsHistory>,
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(window_start_slot: u64)]
+pub struct CommitMetrics<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
     pub config: Account<'info, Config>,
+    #[account(seeds=[METRICS_SEED], bump=metrics_history.bump)]
+    pub metrics_history: Account<'info, MetricsHistory>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, executor.key().as_ref()],
+        bump = executor_record.bump,
+        constraint = executor_record.status == ExecutorStatus::Active @ ErrorCode::ExecutorSuspended
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
     #[account(
         init,
-        payer = submitter,
-        space = 8 + Job::SIZE,
-        seeds = [b"job", commitment.as_ref(), submitter.key().as_ref()],
+        payer = executor,
+        space = 8 + MetricsCommitment::SIZE,
+        seeds = [METRICS_COMMIT_SEED, &window_start_slot.to_le_bytes()],
         bump
     )]
-    pub job: Account<'info, Job>,
+    pub metrics_commitment: Account<'info, MetricsCommitment>,
     #[account(mut)]
-    pub submitter: Signer<'info>,
+    pub executor: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct PostResult<'info> {
+pub struct ChallengeMetrics<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
     #[account(
         mut,
-        seeds=[b"job", &job.commitment, job.submitter.as_ref()],
-        bump=job.bump
+        seeds = [METRICS_COMMIT_SEED, &metrics_commitment.window_start_slot.to_le_bytes()],
+        bump = metrics_commitment.bump
     )]
-    pub job: Account<'info, Job>,
+    pub metrics_commitment: Account<'info, MetricsCommitment>,
     #[account(
-        seeds=[b"config"],
-        bump=config.bump,
-        constraint = config.executor == executor.key() @ ErrorCode::UnauthorizedExecutor
+        mut,
+        seeds = [EXECUTOR_SEED, metrics_commitment.executor.as_ref()],
+        bump = executor_record.bump
     )]
-    pub config: Account<'info, Config>,
-    pub executor: Signer<'info>,
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Finalize<'info> {
-    #[account(seeds=[b"config"], bump=config.bump)]
+pub struct ProveInvalidContribution<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
     pub config: Account<'info, Config>,
     #[account(
         mut,
-        seeds=[b"job", &job.commitment, job.submitter.as_ref()],
-        bump=job.bump
+        seeds = [METRICS_COMMIT_SEED, &metrics_commitment.window_start_slot.to_le_bytes()],
+        bump = metrics_commitment.bump
     )]
-    pub job: Account<'info, Job>,
+    pub metrics_commitment: Account<'info, MetricsCommitment>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, metrics_commitment.executor.as_ref()],
+        bump = executor_record.bump
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    /// CHECK: 루트에 포함된 기여 Job - Job 여부/상태는 metrics::load_job / was_finalized_at에서 판정
+    pub contribution_job: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = metrics_commitment.challenger == Some(challenger.key()) @ ErrorCode::NotChallenger
+    )]
+    pub challenger: Signer<'info>,
+    /// CHECK: 슬래싱 잔여분 수령 계정 (config.treasury와 일치)
+    #[account(mut, address = config.treasury @ ErrorCode::BadTreasury)]
+    pub treasury: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-pub struct PublishMetrics<'info> {
+pub struct ResolveMetricsChallenge<'info> {
     #[account(
-        seeds=[b"config"],
+        seeds=[CONFIG_SEED],
         bump=config.bump,
-        constraint = config.executor == executor.key() @ ErrorCode::UnauthorizedExecutor
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
     )]
     pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [METRICS_COMMIT_SEED, &metrics_commitment.window_start_slot.to_le_bytes()],
+        bump = metrics_commitment.bump
+    )]
+    pub metrics_commitment: Account<'info, MetricsCommitment>,
+    #[account(
+        mut,
+        seeds = [EXECUTOR_SEED, metrics_commitment.executor.as_ref()],
+        bump = executor_record.bump
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    /// CHECK: 슬래싱 보상 수령 계정 (metrics_commitment.challenger와 일치)
+    #[account(mut, constraint = metrics_commitment.challenger == Some(challenger.key()) @ ErrorCode::NotChallenger)]
+    pub challenger: UncheckedAccount<'info>,
+    /// CHECK: 슬래싱 잔여분 수령 계정 (config.treasury와 일치)
+    #[account(mut, address = config.treasury @ ErrorCode::BadTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMetricsCommitment<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = executor,
+        has_one = executor,
+        seeds = [METRICS_COMMIT_SEED, &metrics_commitment.window_start_slot.to_le_bytes()],
+        bump = metrics_commitment.bump
+    )]
+    pub metrics_commitment: Account<'info, MetricsCommitment>,
+    #[account(mut)]
     pub executor: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(oracle_program: Pubkey, feed: Pubkey, price_e9: i64, conf_e9: u64, observed_slot: u64)]
+#[instruction(oracle_program: Pubkey, feed: Pubkey, observed_slot: u64)]
 pub struct RecordOracleSnapshot<'info> {
-    #[account(seeds=[b"config"], bump=config.bump,
-        constraint = config.executor == recorder.key() @ ErrorCode::UnauthorizedExecutor)]
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
     pub config: Account<'info, Config>,
+    #[account(
+        seeds = [EXECUTOR_SEED, recorder.key().as_ref()],
+        bump = executor_record.bump,
+        constraint = executor_record.status == ExecutorStatus::Active @ ErrorCode::ExecutorSuspended
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+
     /// CHECK: 오라클 프로그램 주소 (owner 체크만)
     pub oracle_program_acc: UncheckedAccount<'info>,
-    /// CHECK: 피드(어그리게이터) 계정
+    /// CHECK: 피드(어그리게이터) 계정 - owner/포맷은 oracle::read_feed에서 검증
     pub feed_acc: UncheckedAccount<'info>,
     
     #[account(
@@ -289,7 +2541,7 @@ pub struct RecordOracleSnapshot<'info> {
         payer = recorder,
         space = 8 + OracleSnapshot::SIZE,
         seeds = [
-            b"oraclesnap",
+            ORACLE_SNAPSHOT_SEED,
             oracle_program.as_ref(),
             feed.as_ref(),
             &observed_slot.to_le_bytes()
@@ -303,63 +2555,136 @@ pub struct RecordOracleSnapshot<'info> {
     pub system_program: Program<'info, System>,
 }
 
-/* ========== State ========== */
+#[derive(Accounts)]
+pub struct CloseSnapshot<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = recorder,
+        has_one = recorder,
+        seeds = [
+            ORACLE_SNAPSHOT_SEED,
+            snapshot.oracle_program.as_ref(),
+            snapshot.feed.as_ref(),
+            &snapshot.observed_slot.to_le_bytes()
+        ],
+        bump = snapshot.bump
+    )]
+    pub snapshot: Account<'info, OracleSnapshot>,
+    #[account(mut)]
+    pub recorder: Signer<'info>,
+}
 
-#[account]
-pub struct Config {
-    pub authority: Pubkey,
-    pub executor: Pubkey,
-    pub challenge_window_slots: u64,
-    pub bump: u8,
+#[derive(Accounts)]
+#[instruction(bundle_id: u64)]
+pub struct RecordOracleBundle<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [EXECUTOR_SEED, recorder.key().as_ref()],
+        bump = executor_record.bump,
+        constraint = executor_record.status == ExecutorStatus::Active @ ErrorCode::ExecutorSuspended
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(
+        init,
+        payer = recorder,
+        space = 8 + OracleBundle::SIZE,
+        seeds = [ORACLE_BUNDLE_SEED, recorder.key().as_ref(), &bundle_id.to_le_bytes()],
+        bump
+    )]
+    pub bundle: Account<'info, OracleBundle>,
+    #[account(mut)]
+    pub recorder: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
-impl Config {
-    // authority(32) + executor(32) + challenge_window_slots(8) + bump(1)
-    // = 32 + 32 + 8 + 1 = 73 bytes
-    pub const SIZE: usize = 73;
+
+#[derive(Accounts)]
+pub struct CloseOracleBundle<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = recorder,
+        has_one = recorder,
+        seeds = [ORACLE_BUNDLE_SEED, recorder.key().as_ref(), &bundle.bundle_id.to_le_bytes()],
+        bump = bundle.bump
+    )]
+    pub bundle: Account<'info, OracleBundle>,
+    #[account(mut)]
+    pub recorder: Signer<'info>,
 }
 
-#[account]
-pub struct Job {
-    pub commitment: [u8; 32],
-    pub da_ptr_hash: Option<[u8; 32]>,
-    pub expected_code_digest: [u8; 32],
-    pub result_commitment: Option<[u8; 32]>,
-    pub external_ptr_hash: Option<[u8; 32]>,
-    pub status: JobStatus,
-    pub posted_slot: Option<u64>,
-    pub reveal_after_slot: u64,
-    pub function_id: u16,
-    pub context_data: [u8; 32],
-    pub submitter: Pubkey,
-    pub bump: u8,
+#[derive(Accounts)]
+#[instruction(oracle_program: Pubkey, feed: Pubkey, from_slot: u64, to_slot: u64)]
+pub struct AggregateOracleSnapshots<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [EXECUTOR_SEED, recorder.key().as_ref()],
+        bump = executor_record.bump,
+        constraint = executor_record.status == ExecutorStatus::Active @ ErrorCode::ExecutorSuspended
+    )]
+    pub executor_record: Account<'info, ExecutorRecord>,
+    #[account(
+        init,
+        payer = recorder,
+        space = 8 + AggregatedSnapshot::SIZE,
+        seeds = [
+            ORACLE_AGG_SEED,
+            oracle_program.as_ref(),
+            feed.as_ref(),
+            &from_slot.to_le_bytes(),
+            &to_slot.to_le_bytes()
+        ],
+        bump
+    )]
+    pub aggregated: Account<'info, AggregatedSnapshot>,
+    #[account(mut)]
+    pub recorder: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
-impl Job {
-    // commitment(32) + da_ptr_hash(1+32) + expected_code_digest(32)
-    // + result_commitment(1+32) + external_ptr_hash(1+32) + status(1) + posted_slot(1+8)
-    // + reveal_after_slot(8) + function_id(2) + context_data(32) + submitter(32) + bump(1)
-    // = 32 +33 +32 +33 +33 +1 +9 +8 +2 +32 +32 +1 = 248
-    pub const SIZE: usize = 248;
+
+#[derive(Accounts)]
+pub struct CloseAggregatedSnapshot<'info> {
+    #[account(seeds=[CONFIG_SEED], bump=config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = recorder,
+        has_one = recorder,
+        seeds = [
+            ORACLE_AGG_SEED,
+            aggregated.oracle_program.as_ref(),
+            aggregated.feed.as_ref(),
+            &aggregated.from_slot.to_le_bytes(),
+            &aggregated.to_slot.to_le_bytes()
+        ],
+        bump = aggregated.bump
+    )]
+    pub aggregated: Account<'info, AggregatedSnapshot>,
+    #[account(mut)]
+    pub recorder: Signer<'info>,
 }
 
-#[account]
-#[derive(Default)]
-pub struct OracleSnapshot {
-    pub oracle_program: Pubkey,
-    pub feed: Pubkey,
-    pub price_e9: i64,        // 1e-9 단위
-    pub conf_e9: u64,         // 1e-9 단위의 신뢰구간(선택)
-    pub observed_slot: u64,
-    pub ptr_hash: [u8; 32],   // 아래 hasher로 계산
-    pub bump: u8,
+/* ========== State ========== */
+
+/// 트레이스 리프 + 머클 증명 (리프 개수 = step_count + 1)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TraceLeaf {
+    pub state_hash: [u8; 32],
+    pub proof: Vec<[u8; 32]>,
 }
-impl OracleSnapshot {
-    // 32+32+8+8+8+32+1 = 121 bytes
-    pub const SIZE: usize = 121;
+impl TraceLeaf {
+    pub fn verify(&self, root: &[u8; 32], step: u32, step_count: u32) -> bool {
+        merkle::verify_proof(self.state_hash, step, step_count + 1, &self.proof, root)
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
-#[repr(u8)]
-pub enum JobStatus { Submitted=0, Posted=1, Finalized=2, Revealed=3 }
+/// 분쟁 게임에서 차례인 쪽의 응답 제한 시간
+pub const DISPUTE_MOVE_TIMEOUT_SLOTS: u64 = 150;
+
 
 
 /* ========== Accounts ========== */
@@ -379,20 +2704,170 @@ pub struct JobSubmitted {
     pub expected_code_digest: [u8; 32],
     pub function_id: u16,
     pub context_data: [u8; 32],
+    pub consumer_program: Pubkey,
+    pub ir_account: Option<Pubkey>, // IR 바이트 위치 (IrBuffer 또는 CodeRecord, inline이면 None)
+    pub oracle_guard: Option<OracleGuard>,
+    pub callback: Option<CallbackSpec>,
+    pub caller_program: Option<Pubkey>, // ["coproc-caller"] PDA로 서명해 CPI 제출한 소비 프로그램
+    pub parent_jobs: Vec<Pubkey>,       // job.inputs 끝에 고정된 JobResult 입력
+    pub slot: u64,
+}
+
+#[event]
+pub struct IrBufferSealed {
+    pub ir_buffer: Pubkey,
+    pub owner: Pubkey,
+    pub total_len: u32,
+    pub digest: [u8; 32],
+}
+
+#[event]
+pub struct FunctionRegistered {
+    pub consumer_program: Pubkey,
+    pub function_id: u16,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct FunctionUpdated {
+    pub consumer_program: Pubkey,
+    pub function_id: u16,
+    pub allowed_digests: Vec<[u8; 32]>,
+    pub required_inputs: Vec<InputKind>,
+    pub challenge_window_slots: Option<u64>,
+}
+
+#[event]
+pub struct CodeRegistered {
+    pub code_record: Pubkey,
+    pub digest: [u8; 32],
+    pub name: String,
+    pub version: u16,
+    pub op_count: u32,
+    pub da_ptr_hash: Option<[u8; 32]>,
     pub slot: u64,
 }
 
 #[event]
 pub struct JobPosted {
     pub job: Pubkey,
+    pub executor: Pubkey,
     pub result_commitment: [u8; 32],
     pub code_digest: [u8; 32],
     pub external_ptr_hash: Option<[u8; 32]>,
     pub posted_slot: u64,
+    pub trace_commitment: TraceCommitment,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub challenge_window_slots: u64,
+    pub min_bond_lamports: u64,
+    pub challenger_reward_bp: u16,
+    pub treasury: Pubkey,
+    pub service_deadline_slots: u64,
+    pub snapshot_retention_slots: u64,
+    pub challenger_stake_lamports: u64,
+    pub max_bundle_slot_spread: u64,
+}
+
+#[event] pub struct AuthorityProposed { pub authority: Pubkey, pub pending_authority: Pubkey }
+
+#[event] pub struct AuthorityAccepted { pub previous_authority: Pubkey, pub authority: Pubkey }
+
+#[event] pub struct PauseUpdated { pub paused: bool }
+
+#[event] pub struct FunctionPauseUpdated { pub function_id: u16, pub paused: bool }
+
+#[event] pub struct CommitteeUpdated { pub members: Vec<Pubkey>, pub threshold: u8 }
+
+#[event] pub struct ExecutorAdded { pub executor: Pubkey, pub slot: u64 }
+
+#[event] pub struct ExecutorStatusChanged { pub executor: Pubkey, pub status: ExecutorStatus, pub slot: u64 }
+
+#[event] pub struct ExecutorRemoved { pub executor: Pubkey, pub slot: u64 }
+
+#[event] pub struct BondDeposited { pub executor: Pubkey, pub amount: u64, pub bond_lamports: u64 }
+
+#[event] pub struct BondWithdrawn { pub executor: Pubkey, pub amount: u64, pub bond_lamports: u64 }
+
+#[event]
+pub struct ExecutorSlashed {
+    pub executor: Pubkey,
+    pub job: Pubkey,
+    pub challenger: Pubkey,
+    pub challenger_reward: u64,
+    pub treasury_amount: u64,
+    pub bond_lamports: u64,
 }
 
 #[event] pub struct JobFinalized { pub job: Pubkey, pub slot: u64 }
 
+#[event]
+pub struct JobCancelled {
+    pub job: Pubkey,
+    pub submitter: Pubkey,
+    pub function_id: u16,
+    pub slot: u64,
+}
+
+#[event] pub struct JobRevealed { pub job: Pubkey, pub result: u64, pub slot: u64 }
+
+#[event]
+pub struct CallbackInvoked {
+    pub job: Pubkey,
+    pub program: Pubkey,
+    pub trigger: CallbackTrigger,
+    pub slot: u64,
+}
+
+#[event] pub struct JobConsumed { pub job: Pubkey, pub submitter: Pubkey, pub slot: u64 }
+
+#[event] pub struct JobClosed { pub job: Pubkey, pub submitter: Pubkey, pub slot: u64 }
+
+#[event]
+pub struct JobChallenged {
+    pub job: Pubkey,
+    pub challenger: Pubkey,
+    pub result_commitment: Option<[u8; 32]>,
+    pub evidence_hash: [u8; 32],
+    pub slot: u64,
+    pub stake_lamports: u64,
+}
+
+#[event]
+pub struct ChallengeResolved {
+    pub job: Pubkey,
+    pub challenger: Pubkey,
+    pub executor_at_fault: bool,
+    pub slot: u64,
+    pub stake_lamports: u64, // 인용 시 챌린저에게 반환, 기각 시 treasury로 몰수
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub job: Pubkey,
+    pub dispute: Pubkey,
+    pub challenger: Pubkey,
+    pub defender: Pubkey,
+    pub trace_root: [u8; 32],
+    pub step_count: u32,
+}
+
+#[event] pub struct DisputeBisected { pub job: Pubkey, pub dispute: Pubkey, pub lo: u32, pub hi: u32 }
+
+#[event] pub struct DisputeAborted { pub job: Pubkey, pub dispute: Pubkey, pub slot: u64 }
+
+#[event]
+pub struct DisputeResolved {
+    pub job: Pubkey,
+    pub dispute: Pubkey,
+    pub executor_at_fault: bool,
+    pub lo: u32,
+    pub hi: u32,
+    pub slot: u64,
+}
+
 #[event]
 pub struct MetricsPublished {
     pub window_start_slot: u64,
@@ -402,6 +2877,42 @@ pub struct MetricsPublished {
     pub proof_ptr_hash: Option<[u8; 32]>,
 }
 
+#[event]
+pub struct MetricsCommitted {
+    pub commitment: Pubkey,
+    pub executor: Pubkey,
+    pub window_start_slot: u64,
+    pub tvl: u64,
+    pub contributions_root: [u8; 32],
+    pub contribution_count: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MetricsChallenged {
+    pub commitment: Pubkey,
+    pub challenger: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub slot: u64,
+    pub stake_lamports: u64,
+}
+
+#[event]
+pub struct MetricsChallengeResolved {
+    pub commitment: Pubkey,
+    pub challenger: Pubkey,
+    pub executor_at_fault: bool,
+    pub slot: u64,
+    pub stake_lamports: u64, // 인용 시 챌린저에게 반환, 기각 시 treasury로 몰수
+}
+
+#[event]
+pub struct MetricsCommitmentClosed {
+    pub commitment: Pubkey,
+    pub executor: Pubkey,
+    pub slot: u64,
+}
+
 #[event]
 pub struct OracleSnapshotRecorded {
     pub snapshot: Pubkey,
@@ -413,6 +2924,52 @@ pub struct OracleSnapshotRecorded {
     pub ptr_hash: [u8; 32],
 }
 
+#[event]
+pub struct OracleSnapshotClosed {
+    pub snapshot: Pubkey,
+    pub recorder: Pubkey,
+    pub observed_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OracleBundleRecorded {
+    pub bundle: Pubkey,
+    pub recorder: Pubkey,
+    pub bundle_id: u64,
+    pub feeds: Vec<Pubkey>,
+    pub min_slot: u64,
+    pub max_slot: u64,
+    pub ptr_hash: [u8; 32],
+}
+
+#[event]
+pub struct OracleBundleClosed {
+    pub bundle: Pubkey,
+    pub recorder: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OracleAggregateRecorded {
+    pub aggregated: Pubkey,
+    pub oracle_program: Pubkey,
+    pub feed: Pubkey,
+    pub from_slot: u64,
+    pub to_slot: u64,
+    pub twap_e9: i64,
+    pub median_e9: i64,
+    pub sample_count: u16,
+    pub ptr_hash: [u8; 32],
+}
+
+#[event]
+pub struct OracleAggregateClosed {
+    pub aggregated: Pubkey,
+    pub recorder: Pubkey,
+    pub slot: u64,
+}
+
 /* ========== Errors ========== */
 
 #[error_code]
@@ -430,4 +2987,204 @@ pub enum ErrorCode {
     #[msg("Snapshot hash mismatch")] SnapshotHashMismatch,
     #[msg("Invalid PDA")] InvalidPDA,
     #[msg("Account already initialized")] AccountAlreadyInitialized,
+    #[msg("Challenge window closed")] ChallengeWindowClosed,
+    #[msg("Job is under challenge")] JobUnderChallenge,
+    #[msg("Challenger missing")] MissingChallenger,
+    #[msg("Unauthorized authority")] UnauthorizedAuthority,
+    #[msg("Signer is not the job challenger")] NotChallenger,
+    #[msg("Invalid trace length")] InvalidTraceLength,
+    #[msg("Invalid trace merkle proof")] InvalidTraceProof,
+    #[msg("Invalid dispute phase")] InvalidDisputePhase,
+    #[msg("Not your turn in the dispute")] NotYourTurn,
+    #[msg("Pre-state does not match agreed trace leaf")] PreStateMismatch,
+    #[msg("Dispute move timeout not reached")] DisputeTimeoutNotReached,
+    #[msg("Invalid IR op")] InvalidIrOp,
+    #[msg("Reveal slot not reached")] RevealTooEarly,
+    #[msg("Result commitment missing")] MissingResultCommitment,
+    #[msg("Revealed result does not match commitment")] RevealMismatch,
+    #[msg("Executor is suspended")] ExecutorSuspended,
+    #[msg("Job has no posting executor")] MissingJobExecutor,
+    #[msg("Basis points out of range")] InvalidBasisPoints,
+    #[msg("Insufficient executor bond")] InsufficientBond,
+    #[msg("Executor has unresolved challenges")] OpenChallengesPending,
+    #[msg("Treasury account mismatch")] BadTreasury,
+    #[msg("Committee too large")] CommitteeTooLarge,
+    #[msg("Invalid attestation threshold")] InvalidThreshold,
+    #[msg("Duplicate committee member")] DuplicateCommitteeMember,
+    #[msg("Malformed Ed25519 attestation instruction")] InvalidAttestation,
+    #[msg("Not enough committee attestations")] InsufficientAttestations,
+    #[msg("Signer is not the pending authority")] NotPendingAuthority,
+    #[msg("Coprocessor is paused")] ProgramPaused,
+    #[msg("Function is paused")] FunctionPaused,
+    #[msg("Too many paused functions")] TooManyPausedFunctions,
+    #[msg("Service deadline not passed")] ServiceDeadlineNotPassed,
+    #[msg("Job already consumed")] JobAlreadyConsumed,
+    #[msg("Job not consumed yet")] JobNotConsumed,
+    #[msg("Snapshot still within retention window")] SnapshotRetentionActive,
+    #[msg("IR buffer already sealed")] IrBufferSealed,
+    #[msg("IR buffer not sealed")] IrBufferNotSealed,
+    #[msg("IR chunk offset must equal bytes written")] IrChunkOutOfOrder,
+    #[msg("IR buffer not fully written")] IrBufferIncomplete,
+    #[msg("Code name too long")] CodeNameTooLong,
+    #[msg("Either IR bytes or a DA pointer is required")] MissingCodeSource,
+    #[msg("Declared op count does not match IR")] OpCountMismatch,
+    #[msg("Too many allowed digests")] TooManyAllowedDigests,
+    #[msg("Too many required inputs")] TooManyRequiredInputs,
+    #[msg("Code digest not allowed for this function")] DigestNotAllowed,
+    #[msg("Signer is not the function owner")] NotFunctionOwner,
+    #[msg("Missing external input account or hash")] MissingExternalInput,
+    #[msg("External input does not match external_ptr_hash")] ExternalInputMismatch,
+    #[msg("Input account differs from the one pinned at submit")] PinnedInputMismatch,
+    #[msg("Input job is not finalized")] InputJobNotFinalized,
+    #[msg("Unsupported oracle program")] UnsupportedOracleProgram,
+    #[msg("Oracle feed account data is malformed")] OracleFeedMalformed,
+    #[msg("Oracle feed has no valid price")] OraclePriceUnavailable,
+    #[msg("Oracle value does not fit after normalisation")] OracleValueOverflow,
+    #[msg("Feed account does not match feed argument")] OracleFeedMismatch,
+    #[msg("Observed slot does not match the feed's publish slot")] SnapshotSlotMismatch,
+    #[msg("Oracle guard requires an oracle snapshot or bundle input")] OracleGuardWithoutSnapshot,
+    #[msg("Oracle snapshot older than the job's staleness limit")] OracleSnapshotStale,
+    #[msg("Oracle confidence exceeds the job's band")] OracleConfidenceTooWide,
+    #[msg("Bundle must hold 1..=MAX_BUNDLE_FEEDS feeds")] InvalidBundleSize,
+    #[msg("Feed appears twice in the bundle")] DuplicateBundleFeed,
+    #[msg("Bundle observed slots exceed the allowed spread")] BundleSlotSpreadTooWide,
+    #[msg("Aggregate needs 1..=MAX_AGGREGATE_SAMPLES snapshots")] InvalidSampleCount,
+    #[msg("Snapshot is not for the aggregated feed")] AggregateFeedMismatch,
+    #[msg("Snapshots must be in strictly increasing slot order")] SnapshotsOutOfOrder,
+    #[msg("Snapshot outside the aggregation range")] SnapshotOutOfRange,
+    #[msg("Metrics window must start after the latest one")] MetricsWindowNotMonotonic,
+    #[msg("Metrics window not in history")] MetricsWindowNotFound,
+    #[msg("Signer did not publish this metrics window")] NotMetricsPublisher,
+    #[msg("Contributions root does not match the window's proof_ptr_hash")] MetricsRootMismatch,
+    #[msg("Invalid metrics commitment status")] InvalidMetricsStatus,
+    #[msg("Invalid contribution merkle proof")] InvalidContributionProof,
+    #[msg("Contribution job was finalized at commit time")] ContributionValid,
+    #[msg("Callback program must be the function's consumer program")] CallbackProgramMismatch,
+    #[msg("Callback program account missing from remaining accounts")] CallbackProgramMissing,
+    #[msg("Job cannot depend on itself")] InvalidParentJob,
+    #[msg("Job has no committed trace")] MissingTraceCommitment,
+    #[msg("Trace endpoint does not match the executor's trace commitment")] TraceEndpointMismatch,
+    #[msg("Challenger trace reaches the committed final state")] TraceAgreesWithResult,
+    #[msg("Signer is not the job's consumer program authority")] NotJobConsumer,
+    #[msg("Jobs pinning this job as an input are still open")] JobHasDependents,
+    #[msg("Writable account for a pinned input job is missing")] MissingDependencyAccount,
+    #[msg("Too many jobs depend on this job")] TooManyDependents,
+    #[msg("Oracle snapshot observed beyond the job's staleness window")] OracleSnapshotFromFuture,
+    #[msg("Aggregation window ends after the current slot")] AggregateRangeInFuture,
+    #[msg("Contribution account is not a live job")] ContributionUnprovable,
+    #[msg("Job has no callback")] NoCallback,
+    #[msg("Callback already invoked for this job")] CallbackAlreadyInvoked,
+    #[msg("CPI submission requires the consumer program's caller PDA signature")] CallerAuthorityMissing,
+    #[msg("Caller authority is not the consumer program's caller PDA")] NotCallerAuthority,
+    #[msg("Parent job belongs to another submitter or caller program")] ParentJobNotConsented,
+    #[msg("Final registers do not match the committed trace final state")] FinalStateMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{Discriminator, InstructionData};
+    use coprocessor_interface::instruction as iface;
+
+    #[test]
+    fn interface_discriminators_match_program() {
+        assert_eq!(iface::SubmitJobInlineArgs::DISCRIMINATOR, instruction::SubmitJobInline::DISCRIMINATOR);
+        assert_eq!(iface::SubmitJobByBufferArgs::DISCRIMINATOR, instruction::SubmitJobByBuffer::DISCRIMINATOR);
+        assert_eq!(iface::SubmitJobByDigestArgs::DISCRIMINATOR, instruction::SubmitJobByDigest::DISCRIMINATOR);
+        assert_eq!(iface::FinalizeArgs::DISCRIMINATOR, instruction::Finalize::DISCRIMINATOR);
+        assert_eq!(iface::RevealResultArgs::DISCRIMINATOR, instruction::RevealResult::DISCRIMINATOR);
+        assert_eq!(iface::MarkConsumedArgs::DISCRIMINATOR, instruction::MarkConsumed::DISCRIMINATOR);
+        assert_eq!(iface::CloseJobArgs::DISCRIMINATOR, instruction::CloseJob::DISCRIMINATOR);
+        assert_eq!(iface::CancelJobArgs::DISCRIMINATOR, instruction::CancelJob::DISCRIMINATOR);
+        assert_eq!(iface::InitIrBufferArgs::DISCRIMINATOR, instruction::InitIrBuffer::DISCRIMINATOR);
+        assert_eq!(iface::WriteIrChunkArgs::DISCRIMINATOR, instruction::WriteIrChunk::DISCRIMINATOR);
+        assert_eq!(iface::SealIrBufferArgs::DISCRIMINATOR, instruction::SealIrBuffer::DISCRIMINATOR);
+        assert_eq!(iface::CloseIrBufferArgs::DISCRIMINATOR, instruction::CloseIrBuffer::DISCRIMINATOR);
+        assert_eq!(iface::ChallengeResultArgs::DISCRIMINATOR, instruction::ChallengeResult::DISCRIMINATOR);
+        assert_eq!(iface::ChallengeMetricsArgs::DISCRIMINATOR, instruction::ChallengeMetrics::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn interface_args_match_program_layout() {
+        let guard = Some(OracleGuard { staleness_limit_slots: 300, conf_band_bp: 50 });
+        let callback = Some(CallbackSpec {
+            program_id: Pubkey::new_unique(),
+            discriminator: [7; 8],
+            trigger: CallbackTrigger::Finalize,
+        });
+        let ours = iface::SubmitJobInlineArgs {
+            commitment: [1; 32],
+            da_ptr_hash: Some([2; 32]),
+            reveal_after_slot: 7,
+            function_id: 400,
+            context_data: [3; 32],
+            ir_bytes: vec![0x01, 0x00, 0x01, 0x02],
+            oracle_guard: guard,
+            callback,
+            parent_jobs: vec![Pubkey::new_from_array([5; 32])],
+        };
+        let theirs = instruction::SubmitJobInline {
+            commitment: [1; 32],
+            da_ptr_hash: Some([2; 32]),
+            reveal_after_slot: 7,
+            function_id: 400,
+            context_data: [3; 32],
+            ir_bytes: vec![0x01, 0x00, 0x01, 0x02],
+            oracle_guard: guard,
+            callback,
+            parent_jobs: vec![Pubkey::new_from_array([5; 32])],
+        };
+        assert_eq!(ours.data(), theirs.data());
+
+        let ours = iface::SubmitJobByDigestArgs {
+            commitment: [1; 32],
+            da_ptr_hash: None,
+            reveal_after_slot: 7,
+            function_id: 200,
+            context_data: [3; 32],
+            oracle_guard: None,
+            callback: None,
+            parent_jobs: vec![],
+        };
+        let theirs = instruction::SubmitJobByDigest {
+            commitment: [1; 32],
+            da_ptr_hash: None,
+            reveal_after_slot: 7,
+            function_id: 200,
+            context_data: [3; 32],
+            oracle_guard: None,
+            callback: None,
+            parent_jobs: vec![],
+        };
+        assert_eq!(ours.data(), theirs.data());
+
+        let ours = iface::RevealResultArgs { result: 1, salt: [9; 32], final_step: 2, final_registers: [1; 16] };
+        let theirs = instruction::RevealResult { result: 1, salt: [9; 32], final_step: 2, final_registers: [1; 16] };
+        assert_eq!(ours.data(), theirs.data());
+
+        let ours = iface::WriteIrChunkArgs { offset: 4, chunk: vec![0x10, 0x00, 0x01] };
+        let theirs = instruction::WriteIrChunk { offset: 4, chunk: vec![0x10, 0x00, 0x01] };
+        assert_eq!(ours.data(), theirs.data());
+    }
+
+    #[test]
+    fn interface_pda_helpers_use_program_id() {
+        assert_eq!(coprocessor_interface::ID, ID);
+        let (config, _) = coprocessor_interface::pda::config_pda();
+        assert_eq!(config, Pubkey::find_program_address(&[b"config"], &ID).0);
+
+        let job = Pubkey::new_unique();
+        assert_eq!(
+            coprocessor_interface::pda::dispute_pda(&job).0,
+            Pubkey::find_program_address(&[b"dispute", job.as_ref()], &ID).0
+        );
+        assert_eq!(
+            coprocessor_interface::pda::metrics_commitment_pda(7).0,
+            Pubkey::find_program_address(&[b"metrics-commit", &7u64.to_le_bytes()], &ID).0
+        );
+        assert_eq!(
+            coprocessor_interface::pda::oracle_bundle_pda(&job, 3).0,
+            Pubkey::find_program_address(&[b"oraclebundle", job.as_ref(), &3u64.to_le_bytes()], &ID).0
+        );
+    }
 }
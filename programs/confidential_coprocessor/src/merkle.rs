@@ -0,0 +1,148 @@
+// programs/confidential_coprocessor/src/merkle.rs
+//! keccak 기반 이진 머클 트리
+//! - 내부 노드 = keccak(0x01 || left || right)
+//! - 홀수 개 레벨의 마지막 노드는 해싱 없이 위 레벨로 올림 (리프 1개면 root = leaf)
+use anchor_lang::solana_program::keccak::hashv;
+
+pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[0x01], left, right]).to_bytes()
+}
+
+/// 리프 목록의 루트 (빈 목록은 0 해시)
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [l, r] => hash_pair(l, r),
+                [only] => *only,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/// index 위치의 리프가 root에 포함되는지 검증 (leaf_count로 올림 노드 위치를 판단)
+pub fn verify_proof(
+    leaf: [u8; 32],
+    index: u32,
+    leaf_count: u32,
+    proof: &[[u8; 32]],
+    root: &[u8; 32],
+) -> bool {
+    if index >= leaf_count {
+        return false;
+    }
+    let mut node = leaf;
+    let mut idx = index;
+    let mut count = leaf_count;
+    let mut siblings = proof.iter();
+
+    while count > 1 {
+        if idx % 2 == 1 {
+            match siblings.next() {
+                Some(sib) => node = hash_pair(sib, &node),
+                None => return false,
+            }
+        } else if idx + 1 < count {
+            match siblings.next() {
+                Some(sib) => node = hash_pair(&node, sib),
+                None => return false,
+            }
+        }
+        idx /= 2;
+        count = count.div_ceil(2);
+    }
+
+    siblings.next().is_none() && &node == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| [i + 1; 32]).collect()
+    }
+
+    // merkle_root와 같은 규칙으로 index의 형제 노드 수집 (올림 노드는 형제 없음)
+    fn proof(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
+        let mut out = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut idx = index;
+        while level.len() > 1 {
+            let sibling = idx ^ 1;
+            if sibling < level.len() {
+                out.push(level[sibling]);
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [l, r] => hash_pair(l, r),
+                    [only] => *only,
+                    _ => unreachable!(),
+                })
+                .collect();
+            idx /= 2;
+        }
+        out
+    }
+
+    #[test]
+    fn root_of_small_trees() {
+        assert_eq!(merkle_root(&[]), [0u8; 32]);
+        let l = leaves(3);
+        assert_eq!(merkle_root(&l[..1]), l[0]);
+        assert_eq!(merkle_root(&l[..2]), hash_pair(&l[0], &l[1]));
+        // 홀수 개: 마지막 리프는 해싱 없이 올라감
+        assert_eq!(merkle_root(&l), hash_pair(&hash_pair(&l[0], &l[1]), &l[2]));
+    }
+
+    #[test]
+    fn odd_node_is_promoted_at_every_level() {
+        let l = leaves(5);
+        let left = hash_pair(&hash_pair(&l[0], &l[1]), &hash_pair(&l[2], &l[3]));
+        assert_eq!(merkle_root(&l), hash_pair(&left, &l[4]));
+    }
+
+    #[test]
+    fn proofs_verify_for_every_index() {
+        for n in 1..=9u8 {
+            let l = leaves(n);
+            let root = merkle_root(&l);
+            for i in 0..n as usize {
+                assert!(verify_proof(l[i], i as u32, n as u32, &proof(&l, i), &root), "n={n} i={i}");
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_bad_proofs() {
+        let l = leaves(5);
+        let root = merkle_root(&l);
+        let p = proof(&l, 2);
+        assert!(verify_proof(l[2], 2, 5, &p, &root));
+        // 다른 리프 / 다른 위치 / 범위 밖 인덱스
+        assert!(!verify_proof(l[3], 2, 5, &p, &root));
+        assert!(!verify_proof(l[2], 3, 5, &p, &root));
+        assert!(!verify_proof(l[2], 5, 5, &p, &root));
+        // 형제 누락 / 여분 / 변조
+        assert!(!verify_proof(l[2], 2, 5, &p[..p.len() - 1], &root));
+        let mut extra = p.clone();
+        extra.push([0u8; 32]);
+        assert!(!verify_proof(l[2], 2, 5, &extra, &root));
+        let mut tampered = p.clone();
+        tampered[0][0] ^= 1;
+        assert!(!verify_proof(l[2], 2, 5, &tampered, &root));
+        // 올림 노드는 형제가 없으므로 증명 길이가 짧음 - leaf_count가 다르면 실패
+        let last = proof(&l, 4);
+        assert_eq!(last.len(), 1);
+        assert!(verify_proof(l[4], 4, 5, &last, &root));
+        assert!(!verify_proof(l[4], 4, 6, &last, &root));
+    }
+}
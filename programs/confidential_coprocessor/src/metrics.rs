@@ -0,0 +1,30 @@
+// programs/confidential_coprocessor/src/metrics.rs
+//! 지표 기여 커밋 - 윈도우 TVL에 포함된 (Job, 암호화 잔액 핸들) 쌍의 머클 루트
+//! - 리프 = keccak("metrics-leaf-v1" || job || balance_handle)
+//! - 기여 Job은 커밋 시점에 확정 가능(Finalized/Revealed)했어야 하며, 아니면 챌린저가 증명으로 슬래싱 가능
+//!   판정은 커밋 슬롯 기준 - 커밋 이후 close_job으로 닫힌 Job은 증명 대상이 아님
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+use crate::{Job, JobStatus};
+
+pub fn contribution_leaf(job: &Pubkey, balance_handle: &[u8; 32]) -> [u8; 32] {
+    hashv(&[b"metrics-leaf-v1", job.as_ref(), balance_handle]).to_bytes()
+}
+
+/// 기여 계정을 이 프로그램의 Job으로 읽기 - 닫혔거나 Job이 아니면 None
+pub fn load_job(info: &AccountInfo) -> Option<Job> {
+    if info.owner != &crate::ID {
+        return None;
+    }
+    let data = info.try_borrow_data().ok()?;
+    Job::try_deserialize(&mut &data[..]).ok()
+}
+
+/// Job이 slot 시점에 이미 확정 가능했는지 (Finalized/Revealed이고 게시 후 챌린지 윈도우가 slot 이전에 끝남)
+pub fn was_finalized_at(job: &Job, slot: u64) -> bool {
+    matches!(job.status, JobStatus::Finalized | JobStatus::Revealed)
+        && job
+            .posted_slot
+            .is_some_and(|posted| posted.saturating_add(job.challenge_window_slots) <= slot)
+}
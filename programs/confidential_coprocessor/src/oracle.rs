@@ -0,0 +1,374 @@
+// programs/confidential_coprocessor/src/oracle.rs
+//! 오라클 피드 파서 - 스냅샷 값은 피드 계정 데이터에서 직접 읽고 1e-9 단위로 정규화
+//! - Pyth v2 price account: agg.price/agg.conf * 10^expo, agg.pub_slot
+//! - Switchboard v2 aggregator: latest_confirmed_round.result / std_deviation, round_open_slot
+//! - aggregate: 같은 피드 스냅샷 구간의 TWAP/중앙값/최소/최대
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+pub const PYTH_MAINNET_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+pub const PYTH_DEVNET_PROGRAM_ID: Pubkey = pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+pub const SWITCHBOARD_V2_PROGRAM_ID: Pubkey = pubkey!("SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f");
+
+/// 정규화 단위 (1e-9)
+pub const TARGET_EXPO: i32 = -9;
+
+// Pyth v2 price account 오프셋
+pub const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+pub const PYTH_VERSION_2: u32 = 2;
+pub const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+const PYTH_AGG_PUB_SLOT_OFFSET: usize = 232;
+
+// Switchboard v2 AggregatorAccountData 오프셋 (8바이트 discriminator 포함)
+pub const SWITCHBOARD_AGGREGATOR_DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+const SB_ROUND_NUM_SUCCESS_OFFSET: usize = 341;
+const SB_ROUND_OPEN_SLOT_OFFSET: usize = 350;
+const SB_ROUND_RESULT_OFFSET: usize = 366;
+const SB_ROUND_STD_DEV_OFFSET: usize = 386;
+const SB_MAX_SCALE: u32 = 28;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeedReading {
+    pub price_e9: i64,
+    pub conf_e9: u64,
+    pub observed_slot: u64,
+}
+
+/// 피드 계정 owner 확인 후 오라클 프로그램에 맞는 포맷으로 파싱
+pub fn read_feed(oracle_program: &Pubkey, feed_owner: &Pubkey, data: &[u8]) -> Result<FeedReading> {
+    require_keys_eq!(*feed_owner, *oracle_program, ErrorCode::BadOracleFeedOwner);
+    if *oracle_program == PYTH_MAINNET_PROGRAM_ID || *oracle_program == PYTH_DEVNET_PROGRAM_ID {
+        parse_pyth_price(data)
+    } else if *oracle_program == SWITCHBOARD_V2_PROGRAM_ID {
+        parse_switchboard_aggregator(data)
+    } else {
+        err!(ErrorCode::UnsupportedOracleProgram)
+    }
+}
+
+pub fn parse_pyth_price(data: &[u8]) -> Result<FeedReading> {
+    require!(read_u32(data, 0)? == PYTH_MAGIC, ErrorCode::OracleFeedMalformed);
+    require!(read_u32(data, 4)? == PYTH_VERSION_2, ErrorCode::OracleFeedMalformed);
+    require!(read_u32(data, 8)? == PYTH_ACCOUNT_TYPE_PRICE, ErrorCode::OracleFeedMalformed);
+    require!(
+        read_u32(data, PYTH_AGG_STATUS_OFFSET)? == PYTH_STATUS_TRADING,
+        ErrorCode::OraclePriceUnavailable
+    );
+
+    let expo = read_u32(data, PYTH_EXPO_OFFSET)? as i32;
+    let price = read_u64(data, PYTH_AGG_PRICE_OFFSET)? as i64;
+    let conf = read_u64(data, PYTH_AGG_CONF_OFFSET)?;
+    Ok(FeedReading {
+        price_e9: to_i64(to_e9(price as i128, expo)?)?,
+        conf_e9: to_u64(to_e9(conf as i128, expo)?)?,
+        observed_slot: read_u64(data, PYTH_AGG_PUB_SLOT_OFFSET)?,
+    })
+}
+
+pub fn parse_switchboard_aggregator(data: &[u8]) -> Result<FeedReading> {
+    require!(
+        data.len() >= 8 && data[..8] == SWITCHBOARD_AGGREGATOR_DISCRIMINATOR,
+        ErrorCode::OracleFeedMalformed
+    );
+    require!(
+        read_u32(data, SB_ROUND_NUM_SUCCESS_OFFSET)? > 0,
+        ErrorCode::OraclePriceUnavailable
+    );
+
+    let (result, result_scale) = read_sb_decimal(data, SB_ROUND_RESULT_OFFSET)?;
+    let (std_dev, std_dev_scale) = read_sb_decimal(data, SB_ROUND_STD_DEV_OFFSET)?;
+    Ok(FeedReading {
+        price_e9: to_i64(to_e9(result, -(result_scale as i32))?)?,
+        conf_e9: to_u64(to_e9(std_dev.checked_abs().ok_or(ErrorCode::OracleValueOverflow)?, -(std_dev_scale as i32))?)?,
+        observed_slot: read_u64(data, SB_ROUND_OPEN_SLOT_OFFSET)?,
+    })
+}
+
+/// 집계 입력 샘플 (관측 슬롯 오름차순)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub slot: u64,
+    pub price_e9: i64,
+    pub conf_e9: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Aggregate {
+    pub twap_e9: i64,
+    pub median_e9: i64,
+    pub min_e9: i64,
+    pub max_e9: i64,
+    pub conf_e9: u64, // 샘플 중 가장 넓은 신뢰구간
+    pub observed_slot: u64, // 마지막 샘플 슬롯
+}
+
+/// [from_slot, to_slot] 구간 집계 - 샘플 i의 가중치 = 시작(첫 샘플은 from_slot)부터 다음 샘플(마지막은 to_slot)까지의 슬롯 수
+/// 구간 길이가 0이면 TWAP = 마지막 가격, 짝수 개 중앙값은 가운데 두 값의 평균(0 방향 절삭)
+pub fn aggregate(samples: &[Sample], from_slot: u64, to_slot: u64) -> Result<Aggregate> {
+    let (first, last) = match samples {
+        [first, .., last] => (first, last),
+        [only] => (only, only),
+        [] => return err!(ErrorCode::InvalidSampleCount),
+    };
+    require!(
+        samples.windows(2).all(|w| w[0].slot < w[1].slot),
+        ErrorCode::SnapshotsOutOfOrder
+    );
+    require!(from_slot <= first.slot && last.slot <= to_slot, ErrorCode::SnapshotOutOfRange);
+
+    let mut weighted: i128 = 0;
+    for (i, s) in samples.iter().enumerate() {
+        let start = if i == 0 { from_slot } else { s.slot };
+        let next = samples.get(i + 1).map_or(to_slot, |n| n.slot);
+        weighted += s.price_e9 as i128 * (next - start) as i128;
+    }
+    let span = to_slot - from_slot;
+    let twap = if span == 0 { last.price_e9 as i128 } else { weighted / span as i128 };
+
+    let mut prices: Vec<i64> = samples.iter().map(|s| s.price_e9).collect();
+    prices.sort_unstable();
+    let mid = prices.len() / 2;
+    let median = if prices.len().is_multiple_of(2) {
+        (prices[mid - 1] as i128 + prices[mid] as i128) / 2
+    } else {
+        prices[mid] as i128
+    };
+
+    Ok(Aggregate {
+        twap_e9: to_i64(twap)?,
+        median_e9: to_i64(median)?,
+        min_e9: prices[0],
+        max_e9: prices[prices.len() - 1],
+        conf_e9: samples.iter().map(|s| s.conf_e9).max().unwrap_or_default(),
+        observed_slot: last.slot,
+    })
+}
+
+/// mantissa * 10^expo 를 1e-9 단위로 (자릿수 내림은 0 방향 절삭)
+pub fn to_e9(mantissa: i128, expo: i32) -> Result<i128> {
+    let shift = expo - TARGET_EXPO;
+    if shift >= 0 {
+        10i128
+            .checked_pow(shift as u32)
+            .and_then(|p| mantissa.checked_mul(p))
+            .ok_or_else(|| error!(ErrorCode::OracleValueOverflow))
+    } else {
+        // 10^39 이상은 i128 범위를 넘으므로 결과는 0
+        Ok(10i128.checked_pow(shift.unsigned_abs()).map_or(0, |p| mantissa / p))
+    }
+}
+
+/// SwitchboardDecimal { mantissa: i128, scale: u32 }
+fn read_sb_decimal(data: &[u8], offset: usize) -> Result<(i128, u32)> {
+    let bytes = read_bytes::<16>(data, offset)?;
+    let scale = read_u32(data, offset + 16)?;
+    require!(scale <= SB_MAX_SCALE, ErrorCode::OracleFeedMalformed);
+    Ok((i128::from_le_bytes(bytes), scale))
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|s| s.try_into().ok())
+        .ok_or_else(|| error!(ErrorCode::OracleFeedMalformed))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    read_bytes::<4>(data, offset).map(u32::from_le_bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    read_bytes::<8>(data, offset).map(u64::from_le_bytes)
+}
+
+fn to_i64(v: i128) -> Result<i64> {
+    i64::try_from(v).map_err(|_| error!(ErrorCode::OracleValueOverflow))
+}
+
+fn to_u64(v: i128) -> Result<u64> {
+    u64::try_from(v).map_err(|_| error!(ErrorCode::OracleValueOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PYTH_PRICE_ACCOUNT_LEN: usize = 3312;
+    const SWITCHBOARD_AGGREGATOR_LEN: usize = 3851;
+
+    fn pyth_fixture(expo: i32, price: i64, conf: u64, status: u32, pub_slot: u64) -> Vec<u8> {
+        let mut d = vec![0u8; PYTH_PRICE_ACCOUNT_LEN];
+        d[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        d[4..8].copy_from_slice(&PYTH_VERSION_2.to_le_bytes());
+        d[8..12].copy_from_slice(&PYTH_ACCOUNT_TYPE_PRICE.to_le_bytes());
+        d[12..16].copy_from_slice(&(PYTH_PRICE_ACCOUNT_LEN as u32).to_le_bytes());
+        d[20..24].copy_from_slice(&expo.to_le_bytes());
+        d[208..216].copy_from_slice(&price.to_le_bytes());
+        d[216..224].copy_from_slice(&conf.to_le_bytes());
+        d[224..228].copy_from_slice(&status.to_le_bytes());
+        d[232..240].copy_from_slice(&pub_slot.to_le_bytes());
+        d
+    }
+
+    fn switchboard_fixture(result: (i128, u32), std_dev: (i128, u32), num_success: u32, open_slot: u64) -> Vec<u8> {
+        let mut d = vec![0u8; SWITCHBOARD_AGGREGATOR_LEN];
+        d[0..8].copy_from_slice(&SWITCHBOARD_AGGREGATOR_DISCRIMINATOR);
+        d[341..345].copy_from_slice(&num_success.to_le_bytes());
+        d[350..358].copy_from_slice(&open_slot.to_le_bytes());
+        d[366..382].copy_from_slice(&result.0.to_le_bytes());
+        d[382..386].copy_from_slice(&result.1.to_le_bytes());
+        d[386..402].copy_from_slice(&std_dev.0.to_le_bytes());
+        d[402..406].copy_from_slice(&std_dev.1.to_le_bytes());
+        d
+    }
+
+    #[test]
+    fn switchboard_discriminator_matches_anchor_account_name() {
+        let h = anchor_lang::solana_program::hash::hash(b"account:AggregatorAccountData");
+        assert_eq!(h.to_bytes()[..8], SWITCHBOARD_AGGREGATOR_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn pyth_price_normalised_to_e9() {
+        // 25.00000000 ± 0.05 (expo -8)
+        let data = pyth_fixture(-8, 2_500_000_000, 5_000_000, PYTH_STATUS_TRADING, 1_000_000);
+        let r = read_feed(&PYTH_MAINNET_PROGRAM_ID, &PYTH_MAINNET_PROGRAM_ID, &data).unwrap();
+        assert_eq!(r, FeedReading { price_e9: 25_000_000_000, conf_e9: 50_000_000, observed_slot: 1_000_000 });
+    }
+
+    #[test]
+    fn pyth_finer_expo_truncates() {
+        // 1.234567890123 (expo -12) -> 1.234567890
+        let data = pyth_fixture(-12, 1_234_567_890_123, 999, PYTH_STATUS_TRADING, 7);
+        let r = parse_pyth_price(&data).unwrap();
+        assert_eq!(r.price_e9, 1_234_567_890);
+        assert_eq!(r.conf_e9, 0);
+        assert_eq!(r.observed_slot, 7);
+    }
+
+    #[test]
+    fn pyth_negative_price_kept() {
+        let data = pyth_fixture(-2, -150, 3, PYTH_STATUS_TRADING, 1);
+        let r = parse_pyth_price(&data).unwrap();
+        assert_eq!(r.price_e9, -1_500_000_000);
+        assert_eq!(r.conf_e9, 30_000_000);
+    }
+
+    #[test]
+    fn pyth_rejects_non_trading_status() {
+        let data = pyth_fixture(-8, 2_500_000_000, 5_000_000, 2, 1);
+        assert_eq!(parse_pyth_price(&data).unwrap_err(), error!(ErrorCode::OraclePriceUnavailable));
+    }
+
+    #[test]
+    fn pyth_rejects_bad_header() {
+        let mut data = pyth_fixture(-8, 1, 1, PYTH_STATUS_TRADING, 1);
+        data[0] ^= 0xff;
+        assert_eq!(parse_pyth_price(&data).unwrap_err(), error!(ErrorCode::OracleFeedMalformed));
+
+        let mut data = pyth_fixture(-8, 1, 1, PYTH_STATUS_TRADING, 1);
+        data[8..12].copy_from_slice(&1u32.to_le_bytes()); // mapping account
+        assert_eq!(parse_pyth_price(&data).unwrap_err(), error!(ErrorCode::OracleFeedMalformed));
+
+        let data = pyth_fixture(-8, 1, 1, PYTH_STATUS_TRADING, 1);
+        assert_eq!(parse_pyth_price(&data[..200]).unwrap_err(), error!(ErrorCode::OracleFeedMalformed));
+    }
+
+    #[test]
+    fn pyth_overflow_rejected() {
+        let data = pyth_fixture(2, i64::MAX, 0, PYTH_STATUS_TRADING, 1);
+        assert_eq!(parse_pyth_price(&data).unwrap_err(), error!(ErrorCode::OracleValueOverflow));
+    }
+
+    #[test]
+    fn switchboard_result_normalised_to_e9() {
+        // 25.000 ± 0.050
+        let data = switchboard_fixture((25_000, 3), (-50, 3), 3, 2_000_000);
+        let r = read_feed(&SWITCHBOARD_V2_PROGRAM_ID, &SWITCHBOARD_V2_PROGRAM_ID, &data).unwrap();
+        assert_eq!(r, FeedReading { price_e9: 25_000_000_000, conf_e9: 50_000_000, observed_slot: 2_000_000 });
+    }
+
+    #[test]
+    fn switchboard_high_scale_truncates() {
+        // 소수점 18자리 -> 9자리로 절삭
+        let data = switchboard_fixture((1_500_000_000_123_456_789, 18), (0, 0), 1, 9);
+        let r = parse_switchboard_aggregator(&data).unwrap();
+        assert_eq!(r.price_e9, 1_500_000_000);
+        assert_eq!(r.conf_e9, 0);
+    }
+
+    #[test]
+    fn switchboard_rejects_empty_round_and_bad_discriminator() {
+        let data = switchboard_fixture((25_000, 3), (0, 0), 0, 1);
+        assert_eq!(parse_switchboard_aggregator(&data).unwrap_err(), error!(ErrorCode::OraclePriceUnavailable));
+
+        let mut data = switchboard_fixture((25_000, 3), (0, 0), 1, 1);
+        data[0] ^= 0xff;
+        assert_eq!(parse_switchboard_aggregator(&data).unwrap_err(), error!(ErrorCode::OracleFeedMalformed));
+
+        let data = switchboard_fixture((25_000, 29), (0, 0), 1, 1);
+        assert_eq!(parse_switchboard_aggregator(&data).unwrap_err(), error!(ErrorCode::OracleFeedMalformed));
+    }
+
+    fn sample(slot: u64, price_e9: i64, conf_e9: u64) -> Sample {
+        Sample { slot, price_e9, conf_e9 }
+    }
+
+    #[test]
+    fn aggregate_time_weights_and_median() {
+        // 100..110 @ 10, 110..140 @ 20, 140..150 @ 40 -> (100 + 600 + 400) / 50 = 22
+        let samples = [sample(100, 10, 1), sample(110, 20, 5), sample(140, 40, 2)];
+        let agg = aggregate(&samples, 100, 150).unwrap();
+        assert_eq!(
+            agg,
+            Aggregate { twap_e9: 22, median_e9: 20, min_e9: 10, max_e9: 40, conf_e9: 5, observed_slot: 140 }
+        );
+
+        // 첫 샘플이 from_slot부터 가중: 50..110 @ 10 -> (600 + 600 + 400) / 100 = 16
+        assert_eq!(aggregate(&samples, 50, 150).unwrap().twap_e9, 16);
+    }
+
+    #[test]
+    fn aggregate_even_count_median_and_zero_span() {
+        let samples = [sample(5, 30, 0), sample(6, -10, 0), sample(7, 11, 0), sample(8, 20, 0)];
+        assert_eq!(aggregate(&samples, 5, 8).unwrap().median_e9, 15);
+
+        let single = [sample(9, 25_000_000_000, 50_000_000)];
+        let agg = aggregate(&single, 9, 9).unwrap();
+        assert_eq!(agg.twap_e9, 25_000_000_000);
+        assert_eq!(agg.median_e9, 25_000_000_000);
+    }
+
+    #[test]
+    fn aggregate_rejects_bad_ranges() {
+        assert_eq!(aggregate(&[], 0, 10).unwrap_err(), error!(ErrorCode::InvalidSampleCount));
+        let unordered = [sample(10, 1, 0), sample(10, 2, 0)];
+        assert_eq!(aggregate(&unordered, 0, 20).unwrap_err(), error!(ErrorCode::SnapshotsOutOfOrder));
+        let late = [sample(10, 1, 0), sample(30, 2, 0)];
+        assert_eq!(aggregate(&late, 0, 20).unwrap_err(), error!(ErrorCode::SnapshotOutOfRange));
+        assert_eq!(aggregate(&late, 11, 40).unwrap_err(), error!(ErrorCode::SnapshotOutOfRange));
+    }
+
+    #[test]
+    fn feed_owner_and_program_checked() {
+        let data = pyth_fixture(-8, 1, 1, PYTH_STATUS_TRADING, 1);
+        assert_eq!(
+            read_feed(&PYTH_MAINNET_PROGRAM_ID, &Pubkey::new_unique(), &data).unwrap_err(),
+            error!(ErrorCode::BadOracleFeedOwner)
+        );
+        // 지원하지 않는 프로그램
+        let other = Pubkey::new_unique();
+        assert_eq!(read_feed(&other, &other, &data).unwrap_err(), error!(ErrorCode::UnsupportedOracleProgram));
+        // Pyth 프로그램이 소유한 Switchboard 포맷
+        let sb = switchboard_fixture((1, 0), (0, 0), 1, 1);
+        assert_eq!(
+            read_feed(&PYTH_DEVNET_PROGRAM_ID, &PYTH_DEVNET_PROGRAM_ID, &sb).unwrap_err(),
+            error!(ErrorCode::OracleFeedMalformed)
+        );
+    }
+}
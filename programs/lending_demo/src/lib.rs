@@ -4,101 +4,13 @@ use anchor_spl::{
     token::{self, Mint, Token, TokenAccount, Transfer},
     associated_token::AssociatedToken,
 };
-use anchor_lang::solana_program::{
-    instruction::{AccountMeta, Instruction},
-    keccak::hashv,
-    program::invoke,
-    hash::hash,
-};
+use anchor_lang::solana_program::keccak::hashv;
+use confidential_coprocessor::{cpi::accounts as coproc_accounts, cpi_client as coproc_cpi};
+// 계정 레이아웃/명령 인자는 coprocessor-interface 공용 정의 사용 (손수 미러링 금지)
+use coprocessor_interface::{self as coproc_iface, instruction::SubmitJobInlineArgs};
 
 declare_id!("7416mML15yRamg6KTbemgwBZDsXoVmws328Tp8W7Za9y");
 
-pub mod coproc_iface {
-    use super::*;
-    anchor_lang::declare_id!("9FWBcP2fTpjvYpSQmZZm2NkouBifLw5rKtNPyfzkA4zJ");
-
-    #[account]
-    pub struct Config {
-        pub authority: Pubkey,
-        pub executor: Pubkey,
-        pub challenge_window_slots: u64,
-        pub bump: u8,
-    }
-
-    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
-    #[repr(u8)]
-    pub enum JobStatus { Submitted=0, Posted=1, Finalized=2, Revealed=3 }
-
-    #[account]
-    pub struct Job {
-        pub commitment: [u8; 32],
-        pub da_ptr_hash: Option<[u8; 32]>,
-        pub expected_code_digest: [u8; 32],
-        pub result_commitment: Option<[u8; 32]>,
-        pub external_ptr_hash: Option<[u8; 32]>,
-        pub status: JobStatus,
-        pub posted_slot: Option<u64>,
-        pub reveal_after_slot: u64,
-        pub function_id: u16,
-        pub context_data: [u8; 32],
-        pub submitter: Pubkey,
-        pub bump: u8,
-    }
-
-    pub fn disc(name: &str) -> [u8; 8] {
-        let h = hash(format!("global:{}", name).as_bytes()).to_bytes();
-        let mut d = [0u8; 8]; 
-        d.copy_from_slice(&h[..8]); 
-        d
-    }
-
-    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-    pub struct SubmitJobInlineArgs {
-        pub commitment: [u8; 32],
-        pub da_ptr_hash: Option<[u8; 32]>,
-        pub reveal_after_slot: u64,
-        pub function_id: u16,
-        pub context_data: [u8; 32],
-        pub ir_bytes: Vec<u8>,
-    }
-}
-
-// OracleSnapshot mirror for reading from coprocessor
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct OracleSnapshotMirror {
-    pub oracle_program: Pubkey,
-    pub feed: Pubkey,
-    pub price_e9: i64,
-    pub conf_e9: u64,
-    pub observed_slot: u64,
-    pub ptr_hash: [u8; 32],
-    pub bump: u8,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct JobMirror {
-    pub commitment: [u8; 32],
-    pub da_ptr_hash: Option<[u8; 32]>,
-    pub expected_code_digest: [u8; 32],
-    pub result_commitment: Option<[u8; 32]>,
-    pub external_ptr_hash: Option<[u8; 32]>,
-    pub status: u8, // JobStatus as u8
-    pub posted_slot: Option<u64>,
-    pub reveal_after_slot: u64,
-    pub function_id: u16,
-    pub context_data: [u8; 32],
-    pub submitter: Pubkey,
-    pub bump: u8,
-}
-
-// Local oracle hash computation for verification
-fn oracle_ptr_hash_local(op:&Pubkey, feed:&Pubkey, p:i64, c:u64, s:u64)->[u8;32]{
-    anchor_lang::solana_program::keccak::hashv(&[
-        b"oracle-snap-v1", op.as_ref(), feed.as_ref(),
-        &p.to_le_bytes(), &c.to_le_bytes(), &s.to_le_bytes()
-    ]).to_bytes()
-}
-
 // Function IDs for coprocessor job types
 pub const FID_DEPOSIT:  u16 = 100;
 pub const FID_BORROW:   u16 = 200;
@@ -178,6 +90,27 @@ pub struct CpiSubmitJobCommonArgs {
 }
 
 impl CpiSubmitJobCommonArgs {
+    /// 공통 인자 + 함수별 값 → coprocessor submit_job_inline 인자
+    pub fn into_inline_args(
+        self,
+        function_id: u16,
+        ir_bytes: Vec<u8>,
+        oracle_guard: Option<coproc_iface::OracleGuard>,
+        callback: Option<coproc_iface::CallbackSpec>,
+    ) -> SubmitJobInlineArgs {
+        SubmitJobInlineArgs {
+            commitment: self.commitment,
+            da_ptr_hash: self.da_ptr_hash,
+            reveal_after_slot: self.reveal_after_slot,
+            function_id,
+            context_data: self.context_data,
+            ir_bytes,
+            oracle_guard,
+            callback,
+            parent_jobs: Vec::new(),
+        }
+    }
+
     pub fn create_deposit_context(user: &Pubkey, mint: &Pubkey, amount: u64, nonce: u64) -> [u8; 32] {
         hashv(&[
             b"deposit-v1",
@@ -269,14 +202,13 @@ pub mod lending_demo {
             &ctx.accounts.coproc_program.to_account_info(),
             &ctx.accounts.coproc_config.to_account_info(),
             &ctx.accounts.coproc_job.to_account_info(),
+            &ctx.accounts.coproc_function_record.to_account_info(),
             &ctx.accounts.user.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
-            base.commitment,
-            base.da_ptr_hash,
-            base.reveal_after_slot,
-            FID_DEPOSIT,
-            base.context_data,
-            deposit_ir,
+            &ctx.accounts.coproc_caller.to_account_info(),
+            ctx.bumps.coproc_caller,
+            &[],
+            base.into_inline_args(FID_DEPOSIT, deposit_ir, None, None),
         )
     }
 
@@ -290,14 +222,13 @@ pub mod lending_demo {
             &ctx.accounts.coproc_program.to_account_info(),
             &ctx.accounts.coproc_config.to_account_info(),
             &ctx.accounts.coproc_job.to_account_info(),
+            &ctx.accounts.coproc_function_record.to_account_info(),
             &ctx.accounts.user.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
-            base.commitment,
-            base.da_ptr_hash,
-            base.reveal_after_slot,
-            FID_BORROW,
-            base.context_data,
-            borrow_ir,
+            &ctx.accounts.coproc_caller.to_account_info(),
+            ctx.bumps.coproc_caller,
+            &[],
+            base.into_inline_args(FID_BORROW, borrow_ir, None, None),
         )
     }
 
@@ -307,35 +238,36 @@ pub mod lending_demo {
             &ctx.accounts.coproc_program.to_account_info(),
             &ctx.accounts.coproc_config.to_account_info(),
             &ctx.accounts.coproc_job.to_account_info(),
+            &ctx.accounts.coproc_function_record.to_account_info(),
             &ctx.accounts.user.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
-            base.commitment,
-            base.da_ptr_hash,
-            base.reveal_after_slot,
-            FID_WITHDRAW,
-            base.context_data,
-            withdraw_ir,
+            &ctx.accounts.coproc_caller.to_account_info(),
+            ctx.bumps.coproc_caller,
+            &[],
+            base.into_inline_args(FID_WITHDRAW, withdraw_ir, None, None),
         )
     }
 
-    pub fn submit_liq_eligibility_job(
-        ctx: Context<CpiSubmitJob>,
+    /// oracle_guard = create_oracle_anchored_ctx에 해싱한 staleness/conf band - 코프로세서가 post_result에서 강제
+    /// remaining_accounts[0] = 판정에 쓸 오라클 스냅샷(또는 집계 스냅샷) - 제출 시 Job에 고정
+    pub fn submit_liq_eligibility_job<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CpiSubmitJob<'info>>,
         base: CpiSubmitJobCommonArgs,
         min_collateral_ratio_bp: u32,
+        oracle_guard: Option<coproc_iface::OracleGuard>,
     ) -> Result<()> {
         let ir = build_liq_eligibility_ir(min_collateral_ratio_bp);
         cpi_submit_job_inline(
             &ctx.accounts.coproc_program.to_account_info(),
             &ctx.accounts.coproc_config.to_account_info(),
             &ctx.accounts.coproc_job.to_account_info(),
+            &ctx.accounts.coproc_function_record.to_account_info(),
             &ctx.accounts.user.to_account_info(),
             &ctx.accounts.system_program.to_account_info(),
-            base.commitment,
-            base.da_ptr_hash,
-            base.reveal_after_slot,
-            FID_LIQ_ELIGIBILITY,
-            base.context_data,
-            ir,
+            &ctx.accounts.coproc_caller.to_account_info(),
+            ctx.bumps.coproc_caller,
+            ctx.remaining_accounts,
+            base.into_inline_args(FID_LIQ_ELIGIBILITY, ir, oracle_guard, Some(liq_result_callback())),
         )
     }
 
@@ -347,14 +279,22 @@ pub mod lending_demo {
         require!(ctx.accounts.coproc_config.owner == &coproc_iface::ID, LendErr::BadCoprocConfigOwner);
         
         let job_data = ctx.accounts.coproc_job.try_borrow_data()?;
-        let job = coproc_iface::Job::try_deserialize(&mut &job_data[8..])?;
+        let job = coproc_iface::Job::try_deserialize(&mut &job_data[..])
+            .map_err(|_| error!(LendErr::JobDeserializeFail))?;
+        require_keys_eq!(
+            ctx.accounts.coproc_job.key(),
+            coproc_iface::pda::job_pda(&job.commitment, &job.submitter).0,
+            LendErr::BadJobPda
+        );
         
         let user = &ctx.accounts.user;
         let mint = &ctx.accounts.mint;
 
-        // Validate job status and type
-        require!(matches!(job.status, coproc_iface::JobStatus::Finalized), LendErr::JobNotFinal);
+        // Validate job status and type (누구나 reveal_result를 호출할 수 있으므로 Revealed도 허용)
+        require!(job.is_settled(), LendErr::JobNotFinal);
         require!(job.function_id == FID_WITHDRAW, LendErr::BadFunction);
+        require_keys_eq!(job.consumer_program, crate::ID, LendErr::BadFunctionRecord);
+        require!(job.caller_program == Some(crate::ID), LendErr::NotSubmittedByLending);
         require_keys_eq!(job.submitter, user.key(), LendErr::NotJobSubmitter);
         
         // Validate IR digest to prevent malicious executor
@@ -373,6 +313,10 @@ pub mod lending_demo {
         // Check vault balance
         let vault = &ctx.accounts.vault;
         require!(vault.amount >= amount, LendErr::InsufficientVaultBalance);
+        // 공개된 결과가 있으면 출금 후 잔액(withdraw IR = 잔액 - 출금액)과 대조
+        if let Some(remaining) = job.revealed_result {
+            require!(vault.amount - amount == remaining, LendErr::WithdrawResultMismatch);
+        }
 
         // Transfer tokens from vault to user
         let mint_key = mint.key();
@@ -403,32 +347,48 @@ pub mod lending_demo {
 
         // Load and validate coprocessor job
         let job_data = ctx.accounts.coproc_job.try_borrow_data()?;
-        require!(job_data.len() >= 8, LendErr::JobDeserializeFail);
-        
-        let job = JobMirror::deserialize(&mut &job_data[8..])?;
+        let job = coproc_iface::Job::try_deserialize(&mut &job_data[..])
+            .map_err(|_| error!(LendErr::JobDeserializeFail))?;
+        require_keys_eq!(
+            ctx.accounts.coproc_job.key(),
+            coproc_iface::pda::job_pda(&job.commitment, &job.submitter).0,
+            LendErr::BadJobPda
+        );
         
-        // Validate job status and type
-        require!(job.status == 2, LendErr::JobNotFinalized);
+        // Validate job status and type (누구나 reveal_result를 호출할 수 있으므로 Revealed도 허용)
+        require!(job.is_settled(), LendErr::JobNotFinalized);
         require!(job.function_id == 400, LendErr::WrongJobFunction);
+        require_keys_eq!(job.consumer_program, crate::ID, LendErr::BadFunctionRecord);
+        require!(job.caller_program == Some(crate::ID), LendErr::NotSubmittedByLending);
         require!(job.external_ptr_hash.is_some(), LendErr::MissingExternalPtr);
+        // 공개된 결과가 있으면 실제 판정값 사용 (0 = 청산 불가)
+        if let Some(eligible) = job.revealed_result {
+            require!(eligible != 0, LendErr::NotLiquidatable);
+        }
         
         let external_ptr_hash = job.external_ptr_hash.unwrap();
 
-        // Load and validate oracle snapshot
+        // Load and validate oracle snapshot (단일 스냅샷 또는 집계 스냅샷)
         let snap_info = &ctx.accounts.oracle_snapshot;
         require!(snap_info.owner == &coproc_iface::ID, LendErr::BadCoprocConfigOwner);
-        let snap_data = snap_info.try_borrow_data()?;
-        require!(snap_data.len() >= 8, LendErr::SnapshotDeserializeFail);
-        let snap = OracleSnapshotMirror::deserialize(&mut &snap_data[8..])?;
+        let snap = load_pinned_price(snap_info)?;
+
+        // 제출 시 고정된 스냅샷이어야 하고, 피드는 이 mint에 지정된 청산 오라클이어야 함
+        require!(
+            job.inputs.first().and_then(|input| input.pinned) == Some(snap_info.key()),
+            LendErr::SnapshotNotPinned
+        );
+        let liq_oracle = &ctx.accounts.liq_oracle;
+        require!(
+            snap.oracle_program == liq_oracle.oracle_program && snap.feed == liq_oracle.feed,
+            LendErr::WrongOracleFeed
+        );
 
         // Verify job external_ptr_hash matches snapshot ptr_hash
         require!(external_ptr_hash == snap.ptr_hash, LendErr::SnapshotHashMismatch);
         
         // Verify snapshot integrity by recomputing hash locally
-        require!(
-            snap.ptr_hash == oracle_ptr_hash_local(&snap.oracle_program, &snap.feed, snap.price_e9, snap.conf_e9, snap.observed_slot),
-            LendErr::OraclePtrRehashMismatch
-        );
+        require!(snap.ptr_hash == snap.rehash, LendErr::OraclePtrRehashMismatch);
 
         // Check snapshot freshness (300 slot window)
         let clock = Clock::get()?;
@@ -453,30 +413,131 @@ pub mod lending_demo {
         Ok(())
     }
 
+    /// 담보 mint의 청산 오라클 지정/변경 - mint authority 전용
+    pub fn set_liq_oracle(ctx: Context<SetLiqOracle>, oracle_program: Pubkey, feed: Pubkey) -> Result<()> {
+        let liq_oracle = &mut ctx.accounts.liq_oracle;
+        liq_oracle.mint = ctx.accounts.mint.key();
+        liq_oracle.oracle_program = oracle_program;
+        liq_oracle.feed = feed;
+        liq_oracle.bump = ctx.bumps.liq_oracle;
+        Ok(())
+    }
+
+    /// 끝난 자기 Job 정리 - ["coproc-caller"] PDA로 소비 표시 후 Job을 닫아 rent 회수
+    /// remaining_accounts = Job이 JobResult로 고정한 부모 Job들 (close_job으로 전달)
+    pub fn release_job<'info>(ctx: Context<'_, '_, 'info, 'info, ReleaseJob<'info>>) -> Result<()> {
+        {
+            let coproc_job = &ctx.accounts.coproc_job;
+            require!(coproc_job.owner == &coproc_iface::ID, LendErr::BadCoprocConfigOwner);
+            let job_data = coproc_job.try_borrow_data()?;
+            let job = coproc_iface::Job::try_deserialize(&mut &job_data[..])
+                .map_err(|_| error!(LendErr::JobDeserializeFail))?;
+            require_keys_eq!(job.consumer_program, crate::ID, LendErr::BadFunctionRecord);
+            require_keys_eq!(job.submitter, ctx.accounts.user.key(), LendErr::NotJobSubmitter);
+        }
+
+        let bump = ctx.bumps.coproc_caller;
+        let signer_seeds: &[&[u8]] = &[coproc_iface::pda::CALLER_SEED, &[bump]];
+        coproc_cpi::mark_consumed(CpiContext::new_with_signer(
+            ctx.accounts.coproc_program.to_account_info(),
+            coproc_accounts::MarkConsumed {
+                job: ctx.accounts.coproc_job.to_account_info(),
+                consumer: ctx.accounts.coproc_caller.to_account_info(),
+            },
+            &[signer_seeds],
+        ))?;
+        coproc_cpi::close_job(
+            CpiContext::new(
+                ctx.accounts.coproc_program.to_account_info(),
+                coproc_accounts::CloseJob {
+                    job: ctx.accounts.coproc_job.to_account_info(),
+                    submitter: ctx.accounts.user.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        )
+    }
+
     pub fn emit_liq_ticket(ctx: Context<EmitLiqTicket>) -> Result<()> {
-        require!(ctx.accounts.coproc_job.owner == &coproc_iface::ID, LendErr::BadCoprocConfigOwner);
         require!(ctx.accounts.coproc_config.owner == &coproc_iface::ID, LendErr::BadCoprocConfigOwner);
-        
-        let job_data = ctx.accounts.coproc_job.try_borrow_data()?;
-        let job = coproc_iface::Job::try_deserialize(&mut &job_data[8..])?;
-        
-        require!(matches!(job.status, coproc_iface::JobStatus::Finalized), LendErr::JobNotFinal);
-        require!(job.function_id == FID_LIQ_ELIGIBILITY, LendErr::BadFunction);
+        emit_liq_ticket_for(&ctx.accounts.coproc_job)
+    }
 
-        let ticket_digest = job.result_commitment.unwrap_or([0u8; 32]);
-        let clock = Clock::get()?;
+    /// coprocessor 콜백 (CallbackSpec로 등록) - finalize 후 invoke_callback이 호출하면 LiqTicket 발행
+    pub fn on_liq_result(ctx: Context<LiqResultCallback>) -> Result<()> {
+        emit_liq_ticket_for(&ctx.accounts.coproc_job)
+    }
+}
 
-        emit!(LiqTicket {
-            job: ctx.accounts.coproc_job.key(),
-            ticket_digest,
-            asset_pair: *b"SOL/USDC\0\0\0\0\0\0\0\0",
-            lot: 1_000_000_000,
-            discount_bp: 500,
-            deadline_slot: clock.slot + 150,
+/// 청산 판정 Job에 고정된 가격 입력 - OracleSnapshot 또는 AggregatedSnapshot에서 공통 필드만 추림
+struct PinnedPrice {
+    oracle_program: Pubkey,
+    feed: Pubkey,
+    ptr_hash: [u8; 32],
+    rehash: [u8; 32], // 계정 필드로 다시 계산한 ptr_hash
+    observed_slot: u64,
+}
+
+/// 고정 입력 계정 읽기 - coprocessor inputs::input_leaf처럼 discriminator로 집계 스냅샷 구분
+fn load_pinned_price(info: &AccountInfo) -> Result<PinnedPrice> {
+    let data = info.try_borrow_data()?;
+    if data.starts_with(coproc_iface::AggregatedSnapshot::DISCRIMINATOR) {
+        let agg = coproc_iface::AggregatedSnapshot::try_deserialize(&mut &data[..])
+            .map_err(|_| error!(LendErr::SnapshotDeserializeFail))?;
+        return Ok(PinnedPrice {
+            oracle_program: agg.oracle_program,
+            feed: agg.feed,
+            ptr_hash: agg.ptr_hash,
+            rehash: agg.compute_ptr_hash(),
+            observed_slot: agg.observed_slot,
         });
+    }
+    let snap = coproc_iface::OracleSnapshot::try_deserialize(&mut &data[..])
+        .map_err(|_| error!(LendErr::SnapshotDeserializeFail))?;
+    Ok(PinnedPrice {
+        oracle_program: snap.oracle_program,
+        feed: snap.feed,
+        ptr_hash: snap.ptr_hash,
+        rehash: coproc_iface::oracle_ptr_hash(&snap.oracle_program, &snap.feed, snap.price_e9, snap.conf_e9, snap.observed_slot),
+        observed_slot: snap.observed_slot,
+    })
+}
 
-        Ok(())
+/// 확정된 청산 판정 Job으로 LiqTicket 발행 (emit_liq_ticket / on_liq_result 공용)
+fn emit_liq_ticket_for(coproc_job: &AccountInfo) -> Result<()> {
+    require!(coproc_job.owner == &coproc_iface::ID, LendErr::BadCoprocConfigOwner);
+
+    let job_data = coproc_job.try_borrow_data()?;
+    let job = coproc_iface::Job::try_deserialize(&mut &job_data[..])
+        .map_err(|_| error!(LendErr::JobDeserializeFail))?;
+    require_keys_eq!(
+        coproc_job.key(),
+        coproc_iface::pda::job_pda(&job.commitment, &job.submitter).0,
+        LendErr::BadJobPda
+    );
+
+    require!(job.is_settled(), LendErr::JobNotFinal);
+    require!(job.function_id == FID_LIQ_ELIGIBILITY, LendErr::BadFunction);
+    require_keys_eq!(job.consumer_program, crate::ID, LendErr::BadFunctionRecord);
+    require!(job.caller_program == Some(crate::ID), LendErr::NotSubmittedByLending);
+    // 공개된 결과가 있으면 실제 판정값 사용 (0 = 청산 불가)
+    if let Some(eligible) = job.revealed_result {
+        require!(eligible != 0, LendErr::NotLiquidatable);
     }
+
+    let ticket_digest = job.result_commitment.unwrap_or([0u8; 32]);
+    let clock = Clock::get()?;
+
+    emit!(LiqTicket {
+        job: coproc_job.key(),
+        ticket_digest,
+        asset_pair: *b"SOL/USDC\0\0\0\0\0\0\0\0",
+        lot: 1_000_000_000,
+        discount_bp: 500,
+        deadline_slot: clock.slot + 150,
+    });
+
+    Ok(())
 }
 
 /* ===== Events ===== */
@@ -493,56 +554,49 @@ pub struct LiqTicket {
 
 /* ===== CPI 함수들 (무국적 모드) ===== */
 
-/// 인라인 IR 모드 CPI - submit_job_inline 호출
+/// 인라인 IR 모드 CPI - coprocessor 타입 CPI 헬퍼가 job/function_record PDA 검증 후 호출
+/// caller = ["coproc-caller"] PDA, 서명으로 lending_demo 제출임을 증명
+/// pins = 함수의 required_inputs 순서대로 고정할 입력 계정 (remaining_accounts로 전달)
+#[allow(clippy::too_many_arguments)]
 fn cpi_submit_job_inline<'info>(
     coproc_program: &AccountInfo<'info>,
     coproc_config: &AccountInfo<'info>,
     coproc_job: &AccountInfo<'info>,
+    coproc_function_record: &AccountInfo<'info>,
     user: &AccountInfo<'info>,
     system_program: &AccountInfo<'info>,
-    commitment: [u8; 32],
-    da_ptr_hash: Option<[u8; 32]>,
-    reveal_after_slot: u64,
-    function_id: u16,
-    context_data: [u8; 32],
-    ir_bytes: Vec<u8>,
+    caller: &AccountInfo<'info>,
+    caller_bump: u8,
+    pins: &[AccountInfo<'info>],
+    args: SubmitJobInlineArgs,
 ) -> Result<()> {
-    let (expected, _) = Pubkey::find_program_address(
-        &[b"job", &commitment, user.key().as_ref()],
-        &coproc_iface::ID
-    );
-    require_keys_eq!(expected, coproc_job.key(), LendErr::BadJobPda);
-
-    let mut data = Vec::with_capacity(8 + 200 + ir_bytes.len());
-    data.extend_from_slice(&coproc_iface::disc("submit_job_inline"));
-    let args = coproc_iface::SubmitJobInlineArgs {
-        commitment,
-        da_ptr_hash,
-        reveal_after_slot,
-        function_id,
-        context_data,
-        ir_bytes,
-    };
-    data.extend_from_slice(&args.try_to_vec().map_err(|_| error!(LendErr::SerializeFail))?);
-
-    let metas = vec![
-        AccountMeta::new_readonly(coproc_config.key(), false),
-        AccountMeta::new(coproc_job.key(), false),
-        AccountMeta::new(user.key(), true),
-        AccountMeta::new_readonly(system_program.key(), false),
-    ];
-    let ix = Instruction { program_id: coproc_iface::ID, accounts: metas, data };
-    invoke(
-        &ix,
-        &[
-            coproc_program.clone(),
-            coproc_config.clone(),
-            coproc_job.clone(),
-            user.clone(),
-            system_program.clone(),
-        ],
-    )?;
-    Ok(())
+    let signer_seeds: &[&[u8]] = &[coproc_iface::pda::CALLER_SEED, &[caller_bump]];
+    let signer = &[signer_seeds];
+    let ctx = CpiContext::new_with_signer(
+        coproc_program.clone(),
+        coproc_accounts::SubmitJob {
+            config: coproc_config.clone(),
+            job: coproc_job.clone(),
+            function_record: coproc_function_record.clone(),
+            submitter: user.clone(),
+            system_program: system_program.clone(),
+            caller_authority: Some(caller.clone()),
+        },
+        signer,
+    )
+    .with_remaining_accounts(pins.to_vec());
+    coproc_cpi::submit_job_inline(ctx, &crate::ID, args)
+}
+
+/// 청산 판정 Job 콜백 - finalize 후 invoke_callback으로 on_liq_result 호출, LiqTicket 발행
+fn liq_result_callback() -> coproc_iface::CallbackSpec {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(instruction::OnLiqResult::DISCRIMINATOR);
+    coproc_iface::CallbackSpec {
+        program_id: crate::ID,
+        discriminator,
+        trigger: coproc_iface::CallbackTrigger::Finalize,
+    }
 }
 
 
@@ -551,6 +605,19 @@ fn cpi_submit_job_inline<'info>(
 #[account]
 pub struct JobConsumed {} // 8바이트 discriminator만 - 존재 자체가 소비 마커
 
+/// mint별 청산 오라클 - execute_liquidation은 이 피드의 스냅샷으로 판정한 Job만 허용
+#[account]
+pub struct LiqOracle {
+    pub mint: Pubkey,
+    pub oracle_program: Pubkey,
+    pub feed: Pubkey,
+    pub bump: u8,
+}
+impl LiqOracle {
+    // mint(32) + oracle_program(32) + feed(32) + bump(1) = 97 bytes
+    pub const SIZE: usize = 97;
+}
+
 #[derive(Accounts)]
 pub struct Initialize {}
 
@@ -615,6 +682,8 @@ pub struct DepositAndSubmit<'info> {
     /// CHECK: 아직 미생성 Job PDA (conf_coprocessor가 init)
     #[account(mut)]
     pub coproc_job: UncheckedAccount<'info>,
+    /// CHECK: FunctionRecord PDA (["function", lending_demo, function_id]) - conf_coprocessor가 검증
+    pub coproc_function_record: UncheckedAccount<'info>,
     /// CHECK: CPI 대상 프로그램 계정 (실행 가능 + 정확한 주소)
     #[account(executable, address = coproc_iface::id())]
     pub coproc_program: UncheckedAccount<'info>,
@@ -623,6 +692,9 @@ pub struct DepositAndSubmit<'info> {
     pub user: Signer<'info>, // submitter + payer
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    /// CHECK: lending_demo의 ["coproc-caller"] 서명 PDA - coprocessor가 Job.caller_program으로 기록
+    #[account(seeds = [coproc_iface::pda::CALLER_SEED], bump)]
+    pub coproc_caller: UncheckedAccount<'info>,
 }
 
 /// 모든 CPI submit_*에서 공통으로 쓰는 계정 (무국적 모드)
@@ -636,6 +708,8 @@ pub struct CpiSubmitJob<'info> {
     /// CHECK: 미생성 Job PDA (conf_coprocessor가 init)
     #[account(mut)]
     pub coproc_job: UncheckedAccount<'info>,
+    /// CHECK: FunctionRecord PDA (["function", lending_demo, function_id]) - conf_coprocessor가 검증
+    pub coproc_function_record: UncheckedAccount<'info>,
     /// CHECK: CPI 대상 프로그램 계정
     #[account(executable, address = coproc_iface::id())]
     pub coproc_program: UncheckedAccount<'info>,
@@ -643,6 +717,9 @@ pub struct CpiSubmitJob<'info> {
     #[account(mut)]
     pub user: Signer<'info>, // submitter + payer
     pub system_program: Program<'info, System>,
+    /// CHECK: lending_demo의 ["coproc-caller"] 서명 PDA - coprocessor가 Job.caller_program으로 기록
+    #[account(seeds = [coproc_iface::pda::CALLER_SEED], bump)]
+    pub coproc_caller: UncheckedAccount<'info>,
 }
 
 /// Withdraw 실행 (Finalized 검증 + 송금) - 1회성 소비 보장
@@ -673,7 +750,8 @@ pub struct ExecuteWithdraw<'info> {
     // 컨피덴셜 Job/Config 읽기
     /// CHECK: Job account verified manually in function
     pub coproc_job: UncheckedAccount<'info>,
-    /// CHECK: Config account verified manually in function  
+    /// CHECK: coprocessor Config PDA (인터페이스 시드), owner는 함수에서 검증
+    #[account(seeds = [coproc_iface::pda::CONFIG_SEED], bump, seeds::program = coproc_iface::ID)]
     pub coproc_config: UncheckedAccount<'info>,
 
     /// Replay protection marker
@@ -705,11 +783,14 @@ pub struct ExecuteLiquidation<'info> {
 
     /// CHECK: Coprocessor job account verified manually in function
     pub coproc_job: UncheckedAccount<'info>,
-    /// CHECK: Coprocessor config account verified manually in function
+    /// CHECK: coprocessor Config PDA (인터페이스 시드), owner는 함수에서 검증
+    #[account(seeds = [coproc_iface::pda::CONFIG_SEED], bump, seeds::program = coproc_iface::ID)]
     pub coproc_config: UncheckedAccount<'info>,
 
-    /// CHECK: Oracle snapshot account verified manually in function
+    /// CHECK: Oracle snapshot (or aggregated snapshot) account verified manually in function
     pub oracle_snapshot: UncheckedAccount<'info>,
+    #[account(seeds = [b"liq-oracle", mint.key().as_ref()], bump = liq_oracle.bump)]
+    pub liq_oracle: Account<'info, LiqOracle>,
 
     #[account(
         init,
@@ -726,15 +807,54 @@ pub struct ExecuteLiquidation<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetLiqOracle<'info> {
+    #[account(constraint = mint.mint_authority == anchor_lang::solana_program::program_option::COption::Some(authority.key()) @ LendErr::NotMintAuthority)]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + LiqOracle::SIZE,
+        seeds = [b"liq-oracle", mint.key().as_ref()],
+        bump
+    )]
+    pub liq_oracle: Account<'info, LiqOracle>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseJob<'info> {
+    /// CHECK: coprocessor Job - owner/소비 프로그램은 함수에서, PDA는 cpi_client가 검증
+    #[account(mut)]
+    pub coproc_job: UncheckedAccount<'info>,
+    /// CHECK: lending_demo의 ["coproc-caller"] 서명 PDA (데이터 없음)
+    #[account(seeds = [coproc_iface::pda::CALLER_SEED], bump)]
+    pub coproc_caller: UncheckedAccount<'info>,
+    /// CHECK: CPI 대상 프로그램 계정
+    #[account(executable, address = coproc_iface::id())]
+    pub coproc_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>, // Job submitter (rent 환불 대상)
+}
+
 #[derive(Accounts)]
 pub struct EmitLiqTicket<'info> {
     /// CHECK: Job account verified manually in function
     pub coproc_job: UncheckedAccount<'info>,
-    /// CHECK: Config account verified manually in function
+    /// CHECK: coprocessor Config PDA (인터페이스 시드), owner는 함수에서 검증
+    #[account(seeds = [coproc_iface::pda::CONFIG_SEED], bump, seeds::program = coproc_iface::ID)]
     pub coproc_config: UncheckedAccount<'info>,
     pub user: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct LiqResultCallback<'info> {
+    /// CHECK: coprocessor가 전달한 Job 계정 - owner/레이아웃은 emit_liq_ticket_for에서 검증
+    pub coproc_job: UncheckedAccount<'info>,
+}
+
 /* ===== Errors ===== */
 #[error_code]
 pub enum LendErr {
@@ -756,4 +876,11 @@ pub enum LendErr {
     #[msg("Job is not finalized")] JobNotFinalized,
     #[msg("Wrong job function ID")] WrongJobFunction,
     #[msg("Snapshot hash mismatch with job")] SnapshotHashMismatch,
+    #[msg("Revealed result says position is not liquidatable")] NotLiquidatable,
+    #[msg("Job was not submitted under a lending_demo function record")] BadFunctionRecord,
+    #[msg("Job was not submitted through lending_demo CPI")] NotSubmittedByLending,
+    #[msg("Oracle snapshot is not the one pinned by the job")] SnapshotNotPinned,
+    #[msg("Snapshot feed is not the mint's liquidation oracle")] WrongOracleFeed,
+    #[msg("Signer is not the mint authority")] NotMintAuthority,
+    #[msg("Revealed withdraw result does not match the remaining balance")] WithdrawResultMismatch,
 }